@@ -0,0 +1,57 @@
+use std::process::exit;
+
+use rust_blackjack::cards::card::Card;
+use rust_blackjack::error::BlackjackError;
+use rust_blackjack::gameplay::hand::Hand;
+use rust_blackjack::strategy::analyzer::{Analyzer, ShoeComposition};
+
+use crate::args::{arg_value, exit_with_usage, has_flag, rules_from_args};
+
+mod args;
+
+const USAGE: &str =
+    "analyze [--hand \"10S 6H\" --up 10D] [--removed \"5S 5H ...\"] [--export <file>]";
+
+fn main() {
+    if has_flag("--help") {
+        exit_with_usage(USAGE);
+    }
+    if let Err(err) = run() {
+        println!("{}", err);
+        exit(-1);
+    }
+}
+
+fn run() -> Result<(), BlackjackError> {
+    let rules = rules_from_args(USAGE);
+    let mut analyzer = Analyzer::new(rules);
+    println!("Table rules: {}", rules.describe());
+
+    let (hand, up_card) = match (arg_value("--hand", USAGE), arg_value("--up", USAGE)) {
+        (Some(hand), Some(up_card)) => (hand, up_card),
+        (None, None) => {
+            let chart = analyzer.chart()?;
+            print!("{}", chart);
+            if let Some(path) = arg_value("--export", USAGE) {
+                chart.save(&path)?;
+                println!("Chart saved to {}", path);
+            }
+            return Ok(());
+        }
+        _ => exit_with_usage(USAGE),
+    };
+    let hand: Hand = hand.parse()?;
+    let up_card: Card = up_card.parse()?;
+
+    let mut shoe = ShoeComposition::from_decks(rules.number_of_decks);
+    let removed: Hand = arg_value("--removed", USAGE).unwrap_or_default().parse()?;
+    for card in hand.cards.iter().chain(removed.cards.iter()) {
+        shoe.remove(card)?;
+    }
+    shoe.remove(&up_card)?;
+
+    let values = analyzer.evaluate(&hand, &up_card, &shoe)?;
+    println!("{} vs {}: {}", hand, up_card, values);
+    println!("Best play: {}", values.best().0.to_str());
+    Ok(())
+}
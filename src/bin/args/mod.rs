@@ -0,0 +1,80 @@
+//! Command line flags shared by the headless binaries.
+
+use std::env;
+use std::process::exit;
+use std::str::FromStr;
+
+use rust_blackjack::gameplay::rules::{
+    BlackjackPayout, HoleCardRule, NoHoleCardLoss, Surrender, TableRules,
+};
+
+pub const RULES_USAGE: &str = "[--decks <n>] [--penetration <0-1>] [--h17] [--no-das]
+    [--surrender none|late|early] [--payout 3:2|6:5|1:1] [--hole-card peek|obo|enhc]";
+
+pub fn exit_with_usage(usage: &str) -> ! {
+    println!("Usage: {}\n    {}", usage, RULES_USAGE);
+    exit(-1);
+}
+
+pub fn has_flag(name: &str) -> bool {
+    env::args().any(|arg| arg == name)
+}
+
+pub fn arg_value(name: &str, usage: &str) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    match args.next() {
+        Some(value) => Some(value),
+        None => exit_with_usage(usage),
+    }
+}
+
+pub fn parsed_arg<T: FromStr>(name: &str, usage: &str) -> Option<T> {
+    arg_value(name, usage).map(|value| value.parse().unwrap_or_else(|_| exit_with_usage(usage)))
+}
+
+/// Default table rules with the rule flags applied.
+pub fn rules_from_args(usage: &str) -> TableRules {
+    let mut rules = TableRules::default();
+    if let Some(decks) = parsed_arg("--decks", usage) {
+        if decks == 0 {
+            exit_with_usage(usage);
+        }
+        rules.number_of_decks = decks;
+    }
+    if let Some(penetration) = parsed_arg::<f32>("--penetration", usage) {
+        if !(penetration > 0.0 && penetration <= 1.0) {
+            exit_with_usage(usage);
+        }
+        rules.penetration = penetration;
+    }
+    rules.dealer_hits_soft_17 = has_flag("--h17");
+    if has_flag("--no-das") {
+        rules.double_after_split = false;
+    }
+    if let Some(surrender) = arg_value("--surrender", usage) {
+        rules.surrender = match surrender.as_str() {
+            "none" => Surrender::NotAllowed,
+            "late" => Surrender::Late,
+            "early" => Surrender::Early,
+            _ => exit_with_usage(usage),
+        };
+    }
+    if let Some(payout) = arg_value("--payout", usage) {
+        rules.blackjack_payout = match payout.as_str() {
+            "3:2" => BlackjackPayout::ThreeToTwo,
+            "6:5" => BlackjackPayout::SixToFive,
+            "1:1" => BlackjackPayout::EvenMoney,
+            _ => exit_with_usage(usage),
+        };
+    }
+    if let Some(hole_card) = arg_value("--hole-card", usage) {
+        rules.hole_card = match hole_card.as_str() {
+            "peek" => HoleCardRule::Peek,
+            "obo" => HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly),
+            "enhc" => HoleCardRule::NoHoleCard(NoHoleCardLoss::AllBets),
+            _ => exit_with_usage(usage),
+        };
+    }
+    rules
+}
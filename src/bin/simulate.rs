@@ -0,0 +1,91 @@
+use std::process::exit;
+use std::thread;
+
+use rand::{thread_rng, Rng};
+
+use rust_blackjack::simulation::{simulate, SimulationConfig, SimulationStats};
+use rust_blackjack::strategy::chart::Chart;
+use rust_blackjack::strategy::counting::CountingSystem;
+
+use crate::args::{arg_value, exit_with_usage, has_flag, parsed_arg, rules_from_args};
+
+mod args;
+
+const USAGE: &str = "simulate [--hands <n>] [--shoes <n>] [--threads <n>] [--seed <u64>]
+    [--players <1-7>] [--strategy basic|hi-lo|ko|hi-opt-ii|omega-ii|zen] [--chart <file>]";
+
+fn system_from_args() -> Option<CountingSystem> {
+    match arg_value("--strategy", USAGE).as_deref() {
+        None | Some("basic") => None,
+        Some("hi-lo") => Some(CountingSystem::HI_LO),
+        Some("ko") => Some(CountingSystem::KO),
+        Some("hi-opt-ii") => Some(CountingSystem::HI_OPT_II),
+        Some("omega-ii") => Some(CountingSystem::OMEGA_II),
+        Some("zen") => Some(CountingSystem::ZEN),
+        Some(_) => exit_with_usage(USAGE),
+    }
+}
+
+fn main() {
+    if has_flag("--help") {
+        exit_with_usage(USAGE);
+    }
+
+    let rules = rules_from_args(USAGE);
+    let system = system_from_args();
+    let chart_file = arg_value("--chart", USAGE);
+    if system.is_some() && chart_file.is_some() {
+        exit_with_usage(USAGE);
+    }
+    let chart = chart_file.as_ref().map(|path| {
+        Chart::load(path).unwrap_or_else(|err| {
+            println!("{}", err);
+            exit(-1)
+        })
+    });
+    let hands: u64 = parsed_arg("--hands", USAGE).unwrap_or(1_000_000);
+    let shoes: Option<u64> = parsed_arg("--shoes", USAGE);
+    let threads: u64 = parsed_arg::<u64>("--threads", USAGE).unwrap_or(1).max(1);
+    let players: usize = parsed_arg("--players", USAGE).unwrap_or(1);
+    let seed: u64 = parsed_arg("--seed", USAGE).unwrap_or_else(|| thread_rng().gen());
+    if !(1..=7).contains(&players) {
+        exit_with_usage(USAGE);
+    }
+
+    println!("Table rules: {}", rules.describe());
+    match (system, &chart_file) {
+        (Some(system), _) => println!("Strategy: {}", system.name),
+        (None, Some(path)) => println!("Strategy: chart {}", path),
+        (None, None) => println!("Strategy: basic strategy"),
+    }
+    println!("Seed: {} (replay with --seed {})", seed, seed);
+
+    // every thread plays its own table with its own shoe
+    let workers: Vec<_> = (0..threads)
+        .map(|idx| {
+            let config = SimulationConfig {
+                rules,
+                seed: seed.wrapping_add(idx),
+                hands: hands / threads + u64::from(idx < hands % threads),
+                shoes: shoes.map(|shoes| shoes / threads + u64::from(idx < shoes % threads)),
+                players,
+                system,
+                chart: chart.clone(),
+            };
+            thread::spawn(move || simulate(&config))
+        })
+        .collect();
+
+    let mut stats = SimulationStats::default();
+    for worker in workers {
+        match worker.join() {
+            Ok(Ok(thread_stats)) => stats.merge(&thread_stats),
+            Ok(Err(err)) => {
+                println!("Simulation failed: {}", err);
+                exit(-1);
+            }
+            Err(_) => exit(-1),
+        }
+    }
+    print!("{}", stats);
+}
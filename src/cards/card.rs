@@ -1,7 +1,33 @@
 use std::clone::Clone;
+use std::error::Error;
+use std::fmt;
 use std::prelude::v1::derive;
+use std::str::FromStr;
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
+pub enum ParseCardError {
+    Value(i32),
+    Symbol(String),
+    Suit(String),
+    Card(String),
+    Hand(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCardError::Value(value) => write!(f, "invalid card value {}!", value),
+            ParseCardError::Symbol(s) => write!(f, "invalid card symbol {:?}!", s),
+            ParseCardError::Suit(s) => write!(f, "invalid suit {:?}!", s),
+            ParseCardError::Card(s) => write!(f, "invalid card {:?}!", s),
+            ParseCardError::Hand(s) => write!(f, "invalid hand {:?}!", s),
+        }
+    }
+}
+
+impl Error for ParseCardError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CardSymbol {
     Ace,
     Two,
@@ -20,8 +46,24 @@ pub enum CardSymbol {
 }
 
 impl CardSymbol {
-    pub(crate) fn from_value(value: i32) -> CardSymbol {
-        match value {
+    pub const STANDARD: [CardSymbol; 13] = [
+        CardSymbol::Ace,
+        CardSymbol::Two,
+        CardSymbol::Three,
+        CardSymbol::Four,
+        CardSymbol::Five,
+        CardSymbol::Six,
+        CardSymbol::Seven,
+        CardSymbol::Eight,
+        CardSymbol::Nine,
+        CardSymbol::Ten,
+        CardSymbol::Jack,
+        CardSymbol::Queen,
+        CardSymbol::King,
+    ];
+
+    pub fn from_value(value: i32) -> Result<CardSymbol, ParseCardError> {
+        let symbol = match value {
             1 => CardSymbol::Ace,
             2 => CardSymbol::Two,
             3 => CardSymbol::Three,
@@ -36,12 +78,13 @@ impl CardSymbol {
             12 => CardSymbol::Queen,
             13 => CardSymbol::King,
             14 => CardSymbol::Joker,
-            _ => unreachable!("invalid card value {}!", value),
-        }
+            _ => return Err(ParseCardError::Value(value)),
+        };
+        Ok(symbol)
     }
 
-    pub fn to_str(&self) -> &'static str {
-        match &self {
+    pub fn to_str(self) -> &'static str {
+        match self {
             CardSymbol::Ace => "A",
             CardSymbol::Two => "2",
             CardSymbol::Three => "3",
@@ -60,13 +103,37 @@ impl CardSymbol {
     }
 }
 
+impl fmt::Display for CardSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+/// Card codes as dealt in blackjack, there is no code for the joker.
+impl FromStr for CardSymbol {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "A" => Ok(CardSymbol::Ace),
+            "T" => Ok(CardSymbol::Ten),
+            "J" => Ok(CardSymbol::Jack),
+            "Q" => Ok(CardSymbol::Queen),
+            "K" => Ok(CardSymbol::King),
+            number => match number.parse::<i32>() {
+                Ok(value) if (2..=10).contains(&value) => CardSymbol::from_value(value),
+                _ => Err(ParseCardError::Symbol(s.to_string())),
+            },
+        }
+    }
+}
+
 pub enum Colors {
     Red,
     Black,
 }
 
 impl Colors {
-    #[allow(dead_code)]
     pub fn to_str(&self) -> &'static str {
         match &self {
             Colors::Red => "Red",
@@ -75,7 +142,7 @@ impl Colors {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Suit {
     Spade,
     Club,
@@ -84,7 +151,6 @@ pub enum Suit {
 }
 
 impl Suit {
-    #[allow(dead_code)]
     pub fn color(&self) -> Colors {
         match self {
             Suit::Spade => Colors::Black,
@@ -94,7 +160,6 @@ impl Suit {
         }
     }
 
-    #[allow(dead_code)]
     pub fn to_str(self) -> &'static str {
         match self {
             Suit::Spade => "S",
@@ -105,6 +170,27 @@ impl Suit {
     }
 }
 
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "S" => Ok(Suit::Spade),
+            "C" => Ok(Suit::Club),
+            "H" => Ok(Suit::Heart),
+            "D" => Ok(Suit::Diamond),
+            _ => Err(ParseCardError::Suit(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Card {
     pub suit: Suit,
     pub value: CardSymbol,
@@ -112,7 +198,7 @@ pub struct Card {
 }
 
 impl Card {
-    pub(crate) fn new(suit: Suit, value: CardSymbol) -> Self {
+    pub fn new(suit: Suit, value: CardSymbol) -> Self {
         Card {
             suit,
             value,
@@ -132,3 +218,84 @@ impl Card {
         self.revealed
     }
 }
+
+/// Symbol followed by suit, e.g. `AS`, `10H`, `KD`.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let suit_len = match s.chars().last() {
+            Some(suit) if s.len() > suit.len_utf8() => suit.len_utf8(),
+            _ => return Err(ParseCardError::Card(s.to_string())),
+        };
+
+        let (value, suit) = s.split_at(s.len() - suit_len);
+        Ok(Card::new(suit.parse()?, value.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_back_what_it_prints() {
+        for &suit in [Suit::Spade, Suit::Club, Suit::Heart, Suit::Diamond].iter() {
+            assert_eq!(suit.to_string().parse::<Suit>(), Ok(suit));
+            for &value in CardSymbol::STANDARD.iter() {
+                assert_eq!(value.to_string().parse::<CardSymbol>(), Ok(value));
+                let card = Card::new(suit, value);
+                assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+            }
+        }
+
+        assert_eq!(
+            "10H".parse::<Card>(),
+            Ok(Card::new(Suit::Heart, CardSymbol::Ten))
+        );
+        assert_eq!(
+            "th".parse::<Card>(),
+            Ok(Card::new(Suit::Heart, CardSymbol::Ten))
+        );
+        assert_eq!(
+            "qd".parse::<Card>(),
+            Ok(Card::new(Suit::Diamond, CardSymbol::Queen))
+        );
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_blackjack_card() {
+        fn symbol<T>(s: &str) -> Result<T, ParseCardError> {
+            Err(ParseCardError::Symbol(s.to_string()))
+        }
+        assert_eq!("X".parse::<CardSymbol>(), symbol("X"));
+        assert_eq!("1".parse::<CardSymbol>(), symbol("1"));
+        assert_eq!("11".parse::<CardSymbol>(), symbol("11"));
+        assert_eq!("".parse::<CardSymbol>(), symbol(""));
+
+        assert_eq!("XS".parse::<Card>(), symbol("X"));
+        assert_eq!(
+            "AX".parse::<Card>(),
+            Err(ParseCardError::Suit("X".to_string()))
+        );
+        assert_eq!(
+            "A".parse::<Card>(),
+            Err(ParseCardError::Card("A".to_string()))
+        );
+        assert_eq!(
+            "".parse::<Card>(),
+            Err(ParseCardError::Card("".to_string()))
+        );
+        assert_eq!(
+            "AS♠".parse::<Card>(),
+            Err(ParseCardError::Suit("♠".to_string()))
+        );
+        assert_eq!(CardSymbol::from_value(15), Err(ParseCardError::Value(15)));
+    }
+}
@@ -1,16 +1,17 @@
 use std::collections::VecDeque;
+use std::str::FromStr;
 
 use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 
-use crate::cards::card::{Card, CardSymbol, Suit};
+use crate::cards::card::{Card, CardSymbol, ParseCardError, Suit};
+use crate::error::BlackjackError;
 
 pub struct Deck {
     pub cards: VecDeque<Card>,
 }
 
 impl Deck {
-    #[allow(dead_code)]
     pub fn new() -> Self {
         Self {
             cards: VecDeque::from(Self::fresh_cards_vec()),
@@ -18,27 +19,89 @@ impl Deck {
     }
 
     pub fn new_shuffled() -> Self {
-        let mut cards = Self::fresh_cards_vec();
-        let mut rng = thread_rng();
-        cards.shuffle(&mut rng);
+        let mut deck = Self::new();
+        deck.shuffle();
+        deck
+    }
+
+    /// A stacked deck, cards are drawn in the exact order they are given.
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Self {
+            cards: VecDeque::from(cards),
+        }
+    }
+
+    pub fn from_decks(number_of_decks: usize) -> Self {
+        let mut cards = Vec::with_capacity(number_of_decks * 52);
+        for _ in 0..number_of_decks {
+            cards.append(&mut Self::fresh_cards_vec());
+        }
         Self {
             cards: VecDeque::from(cards),
         }
     }
 
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut thread_rng());
+    }
+
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.make_contiguous().shuffle(rng);
+    }
+
     fn fresh_cards_vec() -> Vec<Card> {
         let mut cards: Vec<Card> = Vec::new();
-        let suits = vec![Suit::Spade, Suit::Heart, Suit::Club, Suit::Diamond];
+        let suits = [Suit::Spade, Suit::Heart, Suit::Club, Suit::Diamond];
         for &suit in suits.iter() {
-            for value in 1..14 {
-                // skipping joker for now
-                cards.push(Card::new(suit, CardSymbol::from_value(value)));
+            // skipping joker for now
+            for &value in CardSymbol::STANDARD.iter() {
+                cards.push(Card::new(suit, value));
             }
         }
         cards
     }
 
-    pub fn draw_card(&mut self) -> Card {
-        self.cards.pop_front().unwrap()
+    pub fn draw_card(&mut self) -> Result<Card, BlackjackError> {
+        self.cards.pop_front().ok_or(BlackjackError::OutOfCards)
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses a stacked deck from space separated card codes, first card on top: `"AS KD 8H 8C"`.
+impl FromStr for Deck {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<Card>, ParseCardError>>()?;
+        Ok(Deck::from_cards(cards))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacked_deck_is_dealt_in_order() {
+        let mut deck: Deck = " AS 10H\tKD\n2C ".parse().unwrap();
+        let cards: Vec<String> = (0..4)
+            .map(|_| deck.draw_card().unwrap().to_string())
+            .collect();
+        assert_eq!(cards, ["AS", "10H", "KD", "2C"]);
+        assert!(matches!(deck.draw_card(), Err(BlackjackError::OutOfCards)));
+
+        assert!("".parse::<Deck>().unwrap().cards.is_empty());
+        assert_eq!(
+            "AS 1H KD".parse::<Deck>().err(),
+            Some(ParseCardError::Symbol("1".to_string()))
+        );
     }
 }
@@ -1,2 +1,3 @@
 pub mod card;
 pub mod deck;
+pub mod shoe;
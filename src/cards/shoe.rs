@@ -0,0 +1,228 @@
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::cards::card::Card;
+use crate::cards::deck::Deck;
+use crate::error::BlackjackError;
+
+pub struct Shoe {
+    pub deck: Deck,
+    pub number_of_decks: usize,
+    pub penetration: f32,
+    size: usize,
+    cut_card_position: usize,
+    cut_card_reached: bool,
+    /// Cards played since the last shuffle and collected off the table.
+    discards: Vec<Card>,
+    rng: Box<dyn RngCore>,
+}
+
+impl Shoe {
+    pub fn new(number_of_decks: usize, penetration: f32) -> Result<Self, BlackjackError> {
+        Self::with_rng(
+            number_of_decks,
+            penetration,
+            Box::new(StdRng::from_entropy()),
+        )
+    }
+
+    /// Same seed, same shuffles: every round dealt from this shoe can be replayed.
+    pub fn with_seed(
+        number_of_decks: usize,
+        penetration: f32,
+        seed: u64,
+    ) -> Result<Self, BlackjackError> {
+        Self::with_rng(
+            number_of_decks,
+            penetration,
+            Box::new(StdRng::seed_from_u64(seed)),
+        )
+    }
+
+    /// `InvalidInput` without a deck or with a penetration outside `(0, 1]`.
+    pub fn with_rng(
+        number_of_decks: usize,
+        penetration: f32,
+        rng: Box<dyn RngCore>,
+    ) -> Result<Self, BlackjackError> {
+        if number_of_decks == 0 {
+            return Err(BlackjackError::InvalidInput(format!(
+                "{} decks",
+                number_of_decks
+            )));
+        }
+        if !(penetration > 0.0 && penetration <= 1.0) {
+            return Err(BlackjackError::InvalidInput(format!(
+                "{} penetration",
+                penetration
+            )));
+        }
+
+        let mut shoe = Self {
+            deck: Deck::from_decks(number_of_decks),
+            number_of_decks,
+            penetration,
+            size: 0,
+            cut_card_position: 0,
+            cut_card_reached: false,
+            discards: Vec::new(),
+            rng,
+        };
+        shoe.shuffle();
+        Ok(shoe)
+    }
+
+    /// Replaces the shoe with a stacked deck, dealt as is for replaying exact scenarios.
+    /// The cut card sits under the last stacked card.
+    pub fn stack(&mut self, deck: Deck) {
+        self.size = deck.cards.len();
+        self.cut_card_position = self.size;
+        self.cut_card_reached = false;
+        self.discards.clear();
+        self.deck = deck;
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn remaining_cards(&self) -> usize {
+        self.deck.cards.len()
+    }
+
+    pub fn dealt_cards(&self) -> usize {
+        self.size() - self.remaining_cards()
+    }
+
+    /// Collects all cards back into the shoe, shuffles them and places the cut card.
+    pub fn shuffle(&mut self) {
+        self.discards.clear();
+        self.deck = Deck::from_decks(self.number_of_decks);
+        self.deck.shuffle_with(&mut self.rng);
+        self.size = self.deck.cards.len();
+        self.cut_card_position = (self.size() as f32 * self.penetration) as usize;
+        self.cut_card_reached = false;
+    }
+
+    /// Cards collected off the table once a round is over, they stay out of the shoe until
+    /// it is shuffled.
+    pub fn discard(&mut self, cards: impl IntoIterator<Item = Card>) {
+        self.discards.extend(cards.into_iter().map(|mut card| {
+            card.reveal();
+            card
+        }));
+    }
+
+    /// Shuffles the discards into an empty shoe, for a round that runs out of cards. The cards
+    /// still on the table stay out, the cut card counts as reached so the next round starts
+    /// from a full shuffle. `false` without any discards.
+    pub fn shuffle_discards(&mut self) -> bool {
+        if self.discards.is_empty() {
+            return false;
+        }
+        self.deck = Deck::from_cards(self.discards.split_off(0));
+        self.deck.shuffle_with(&mut self.rng);
+        self.size = self.deck.cards.len();
+        self.cut_card_position = 0;
+        self.cut_card_reached = true;
+        true
+    }
+
+    /// The cut card came out, the shoe should be shuffled before the next round.
+    pub fn needs_shuffle(&self) -> bool {
+        self.cut_card_reached
+    }
+
+    /// `OutOfCards` once the shoe is empty, shuffling is up to the caller.
+    pub fn draw_card(&mut self) -> Result<Card, BlackjackError> {
+        let card = self.deck.draw_card()?;
+        if self.dealt_cards() >= self.cut_card_position {
+            self.cut_card_reached = true;
+        }
+        Ok(card)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deal(shoe: &mut Shoe, cards: usize) -> Vec<String> {
+        (0..cards)
+            .map(|_| shoe.draw_card().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn places_the_cut_card_by_penetration() {
+        let mut shoe = Shoe::with_seed(2, 0.75, 1).unwrap();
+        assert_eq!(shoe.size(), 104);
+        deal(&mut shoe, 77);
+        assert!(!shoe.needs_shuffle());
+        deal(&mut shoe, 1);
+        assert!(shoe.needs_shuffle());
+        assert_eq!(shoe.dealt_cards(), 78);
+
+        shoe.shuffle();
+        assert!(!shoe.needs_shuffle());
+        assert_eq!(shoe.remaining_cards(), 104);
+    }
+
+    #[test]
+    fn runs_out_instead_of_reshuffling() {
+        let mut shoe = Shoe::with_seed(1, 1.0, 1).unwrap();
+        deal(&mut shoe, 51);
+        assert!(!shoe.needs_shuffle());
+        deal(&mut shoe, 1);
+        assert!(shoe.needs_shuffle());
+        assert!(matches!(shoe.draw_card(), Err(BlackjackError::OutOfCards)));
+
+        shoe.stack("AS KD".parse().unwrap());
+        assert_eq!(deal(&mut shoe, 2), ["AS", "KD"]);
+        assert!(shoe.needs_shuffle());
+        assert!(matches!(shoe.draw_card(), Err(BlackjackError::OutOfCards)));
+    }
+
+    #[test]
+    fn reshuffles_only_the_discards() {
+        let mut shoe = Shoe::with_seed(1, 0.75, 1).unwrap();
+        shoe.stack("AS KD 5H".parse().unwrap());
+        assert!(!shoe.shuffle_discards());
+
+        let mut dealt = deal(&mut shoe, 3);
+        shoe.discard(dealt.drain(..2).map(|card| card.parse().unwrap()));
+        assert!(shoe.shuffle_discards());
+        assert!(shoe.needs_shuffle());
+        let mut reshuffled = deal(&mut shoe, 2);
+        reshuffled.sort();
+        assert_eq!(reshuffled, ["AS", "KD"]);
+        assert!(matches!(shoe.draw_card(), Err(BlackjackError::OutOfCards)));
+        assert!(!shoe.shuffle_discards());
+    }
+
+    #[test]
+    fn same_seed_same_order() {
+        let mut first = Shoe::with_seed(6, 0.75, 42).unwrap();
+        let mut second = Shoe::with_seed(6, 0.75, 42).unwrap();
+        let mut other = Shoe::with_seed(6, 0.75, 43).unwrap();
+        for _ in 0..3 {
+            let cards = deal(&mut first, 200);
+            assert_eq!(cards, deal(&mut second, 200));
+            assert_ne!(cards, deal(&mut other, 200));
+            first.shuffle();
+            second.shuffle();
+            other.shuffle();
+        }
+    }
+
+    #[test]
+    fn refuses_shoes_that_cannot_be_dealt() {
+        for (decks, penetration) in [(0, 0.75), (6, 0.0), (6, 1.5), (6, f32::NAN)] {
+            assert!(matches!(
+                Shoe::with_seed(decks, penetration, 1),
+                Err(BlackjackError::InvalidInput(_))
+            ));
+        }
+        assert!(Shoe::with_seed(1, 1.0, 1).is_ok());
+    }
+}
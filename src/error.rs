@@ -0,0 +1,79 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::cards::card::ParseCardError;
+use crate::gameplay::blackjack::UserAction;
+use crate::strategy::chart::ParseChartError;
+
+#[derive(Debug)]
+pub enum BlackjackError {
+    /// Nothing left to draw.
+    OutOfCards,
+    /// No card, hand, actor or player at the index.
+    OutOfRange(usize),
+    /// The action is not available for the hand.
+    InvalidAction(UserAction),
+    /// A hand was settled before it was played out.
+    UnfinishedHand,
+    InsufficientFunds {
+        bankroll: i32,
+        amount: i32,
+    },
+    InvalidInput(String),
+    /// Stdin was closed, nobody is left to answer.
+    InputClosed,
+    Io(io::Error),
+    Card(ParseCardError),
+    Chart(ParseChartError),
+}
+
+impl fmt::Display for BlackjackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlackjackError::OutOfCards => write!(f, "out of cards!"),
+            BlackjackError::OutOfRange(index) => write!(f, "index {} out of range!", index),
+            BlackjackError::InvalidAction(action) => {
+                write!(f, "{} is not allowed for this hand!", action.to_str())
+            }
+            BlackjackError::UnfinishedHand => write!(f, "hand is not finished!"),
+            BlackjackError::InsufficientFunds { bankroll, amount } => {
+                write!(f, "{} coins can't cover {}!", bankroll, amount)
+            }
+            BlackjackError::InvalidInput(s) => write!(f, "invalid input {:?}!", s),
+            BlackjackError::InputClosed => write!(f, "input closed!"),
+            BlackjackError::Io(err) => write!(f, "{}", err),
+            BlackjackError::Card(err) => write!(f, "{}", err),
+            BlackjackError::Chart(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for BlackjackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BlackjackError::Io(err) => Some(err),
+            BlackjackError::Card(err) => Some(err),
+            BlackjackError::Chart(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BlackjackError {
+    fn from(err: io::Error) -> Self {
+        BlackjackError::Io(err)
+    }
+}
+
+impl From<ParseCardError> for BlackjackError {
+    fn from(err: ParseCardError) -> Self {
+        BlackjackError::Card(err)
+    }
+}
+
+impl From<ParseChartError> for BlackjackError {
+    fn from(err: ParseChartError) -> Self {
+        BlackjackError::Chart(err)
+    }
+}
@@ -1,6 +1,7 @@
 use std::vec::Vec;
 
 use crate::at;
+use crate::error::BlackjackError;
 use crate::gameplay::hand::Hand;
 
 #[derive(Debug)]
@@ -16,11 +17,11 @@ pub struct Actor {
 }
 
 impl Actor {
-    pub fn hand_at(&self, at: usize) -> &Hand {
+    pub fn hand_at(&self, at: usize) -> Result<&Hand, BlackjackError> {
         at!(self.hands, at)
     }
 
-    pub fn hand_at_mut(&mut self, at: usize) -> &mut Hand {
+    pub fn hand_at_mut(&mut self, at: usize) -> Result<&mut Hand, BlackjackError> {
         at!(mut self.hands, at)
     }
 
@@ -1,6 +1,6 @@
 use crate::cards::card::CardSymbol;
 
-pub(crate) fn blackjack_card_value(card_symbol: &CardSymbol) -> i32 {
+pub fn blackjack_card_value(card_symbol: &CardSymbol) -> i32 {
     match card_symbol {
         CardSymbol::Two => 2,
         CardSymbol::Three => 3,
@@ -16,8 +16,33 @@ pub(crate) fn blackjack_card_value(card_symbol: &CardSymbol) -> i32 {
     }
 }
 
-pub(crate) enum UserAction {
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UserAction {
     Hit,
     Stay,
+    Double,
     Split,
+    Surrender,
+}
+
+impl UserAction {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            UserAction::Hit => "HIT",
+            UserAction::Stay => "STAY",
+            UserAction::Double => "DOUBLE",
+            UserAction::Split => "SPLIT",
+            UserAction::Surrender => "SURRENDER",
+        }
+    }
+
+    pub fn key(self) -> char {
+        match self {
+            UserAction::Hit => 'h',
+            UserAction::Stay => 's',
+            UserAction::Double => 'd',
+            UserAction::Split => 'x',
+            UserAction::Surrender => 'r',
+        }
+    }
 }
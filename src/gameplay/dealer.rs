@@ -0,0 +1,29 @@
+use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::hand::HandValue;
+use crate::gameplay::rules::TableRules;
+
+/// How the dealer plays out their hand, there are no decisions beyond what the rules say.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DealerPolicy {
+    /// Lowest total the dealer stands on.
+    pub stand_on: i32,
+    pub hits_soft_17: bool,
+}
+
+impl DealerPolicy {
+    pub fn from_rules(rules: &TableRules) -> Self {
+        DealerPolicy {
+            stand_on: 17,
+            hits_soft_17: rules.dealer_hits_soft_17,
+        }
+    }
+
+    /// `Hit` or `Stay` for a hand that is neither bust nor 21.
+    pub fn action(&self, value: &HandValue) -> UserAction {
+        if value.total < self.stand_on || (self.hits_soft_17 && value.soft && value.total == 17) {
+            UserAction::Hit
+        } else {
+            UserAction::Stay
+        }
+    }
+}
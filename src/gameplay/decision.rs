@@ -0,0 +1,54 @@
+use crate::cards::card::Card;
+use crate::error::BlackjackError;
+use crate::gameplay::actor::Actor;
+use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::player::Player;
+
+/// Everything the engine needs to ask the players, `seat` is the player's index at the table.
+/// An `Err` aborts the round, e.g. `BlackjackError::InputClosed` once nobody is left to answer.
+pub trait DecisionProvider {
+    /// Wager for the next round within `minimum..=maximum`, anything outside is clamped.
+    /// `None` cashes out.
+    fn bet(
+        &mut self,
+        seat: usize,
+        player: &Player,
+        minimum: i32,
+        maximum: i32,
+    ) -> Result<Option<i32>, BlackjackError>;
+
+    /// The player can't cover the table minimum, buy in again or leave the table.
+    fn rebuy(&mut self, seat: usize, player: &Player, buy_in: i32) -> Result<bool, BlackjackError>;
+
+    /// Dealer shows an ace, insurance amount up to `maximum`.
+    fn insurance(
+        &mut self,
+        seat: usize,
+        actor: &Actor,
+        maximum: i32,
+    ) -> Result<i32, BlackjackError>;
+
+    /// Dealer shows an ace and the player holds a natural.
+    fn even_money(&mut self, seat: usize, actor: &Actor) -> Result<bool, BlackjackError>;
+
+    /// Surrender before the dealer checks for blackjack.
+    fn early_surrender(
+        &mut self,
+        seat: usize,
+        actor: &Actor,
+        dealer_up_card: &Card,
+    ) -> Result<bool, BlackjackError>;
+
+    /// One of `actions` for the actor's hand at `hand_idx`, anything else is
+    /// `BlackjackError::InvalidAction`.
+    fn action(
+        &mut self,
+        seat: usize,
+        actor: &Actor,
+        hand_idx: usize,
+        dealer_up_card: &Card,
+        actions: &[UserAction],
+    ) -> Result<UserAction, BlackjackError>;
+
+    fn another_round(&mut self) -> Result<bool, BlackjackError>;
+}
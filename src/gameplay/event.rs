@@ -0,0 +1,121 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::cards::card::Card;
+use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::game::HandResult;
+
+/// Everything that happens at the table, in order. Actors are indexed as in `Round::actors`,
+/// the dealer is always the last one.
+#[derive(Debug, Clone)]
+pub enum GameEvent {
+    ShoeShuffled {
+        number_of_decks: usize,
+    },
+    /// The shoe ran out mid-round and the discards were shuffled back in, the cards on the
+    /// table stay out until the next `ShoeShuffled`.
+    DiscardsShuffled {
+        cards: usize,
+    },
+    /// Players in seat order followed by the dealer, with the main bet of every player.
+    RoundStarted {
+        actors: Vec<String>,
+        bets: Vec<i32>,
+    },
+    /// A card dealt face up.
+    CardDealt {
+        actor_idx: usize,
+        hand_idx: usize,
+        card: Card,
+    },
+    /// The dealer's hole card is dealt face down, nobody learns its value before
+    /// `HoleCardRevealed`.
+    HoleCardDealt {
+        actor_idx: usize,
+    },
+    InitialCardsDealt,
+    InsuranceTaken {
+        actor_idx: usize,
+        amount: i32,
+    },
+    EvenMoneyTaken {
+        actor_idx: usize,
+    },
+    DealerPeeked {
+        blackjack: bool,
+    },
+    HoleCardRevealed {
+        card: Card,
+    },
+    ActionTaken {
+        actor_idx: usize,
+        hand_idx: usize,
+        action: UserAction,
+    },
+    /// The second card of the hand moved to a new hand right after it.
+    HandSplit {
+        actor_idx: usize,
+        hand_idx: usize,
+    },
+    HandBusted {
+        actor_idx: usize,
+        hand_idx: usize,
+    },
+    HandNatural {
+        actor_idx: usize,
+        hand_idx: usize,
+    },
+    HandTwentyOne {
+        actor_idx: usize,
+        hand_idx: usize,
+    },
+    InsuranceSettled {
+        player: String,
+        coins: i32,
+    },
+    HandSettled {
+        player: String,
+        hand_idx: usize,
+        result: HandResult,
+        coins: i32,
+    },
+    PlayerRebought {
+        player: String,
+        amount: i32,
+    },
+    PlayerSatOut {
+        player: String,
+    },
+    PlayerCashedOut {
+        player: String,
+        bankroll: i32,
+    },
+}
+
+pub trait GameObserver {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+/// Lets an observer be registered while someone else, e.g. a counting bot, keeps reading it.
+impl<T: GameObserver> GameObserver for Rc<RefCell<T>> {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.borrow_mut().on_event(event);
+    }
+}
+
+#[derive(Default)]
+pub struct Observers {
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+impl Observers {
+    pub fn register(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    pub fn emit(&mut self, event: GameEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.on_event(&event);
+        }
+    }
+}
@@ -1,80 +1,971 @@
+use std::cmp::Ordering;
+
 use crate::at;
+use crate::cards::shoe::Shoe;
+use crate::error::BlackjackError;
+use crate::gameplay::decision::DecisionProvider;
+use crate::gameplay::event::{GameEvent, Observers};
 use crate::gameplay::hand::{Hand, HandState};
-use crate::gameplay::round::Round;
+use crate::gameplay::player::Player;
+use crate::gameplay::round::{blackjack_round, shuffle_if_needed, Round, Seat};
+use crate::gameplay::rules::{HoleCardRule, NoHoleCardLoss, Surrender, TableRules};
 
-#[allow(dead_code)]
 pub struct Game {
-    pub player_scores: Vec<i32>,
-    pub player_names: Vec<String>,
+    pub players: Vec<Player>,
+    /// Coins a player gets for every buy-in.
+    pub buy_in: i32,
+    pub shoe: Shoe,
+    pub rules: TableRules,
+    pub decisions: Box<dyn DecisionProvider>,
+    pub observers: Observers,
 }
 
 impl Game {
-    pub fn print_player_scores(&self) {
-        println!();
-        println!("Scores:");
-        println!("=======");
-        self.player_names.iter().enumerate().for_each(|(i, name)| {
-            println!("{}: {} coins", name, at!(self.player_scores, i));
-        });
+    /// Plays a full round from bets to payouts, false once nobody is left to play.
+    /// Bankrolls are untouched when the round is aborted by an `Err`.
+    pub fn play_round(&mut self) -> Result<bool, BlackjackError> {
+        if !self.has_seated_players() {
+            return Ok(false);
+        }
+
+        // shuffle before the bets, so the players know the shoe they are betting on
+        shuffle_if_needed(&mut self.shoe, &mut self.observers);
+        self.offer_rebuys()?;
+        let seats = self.take_bets()?;
+        if seats.is_empty() {
+            return Ok(false);
+        }
+
+        let mut round = blackjack_round(&seats, self.rules);
+        let played = round.play(&mut self.shoe, self.decisions.as_mut(), &mut self.observers);
+        let cards = round.actors.iter().flat_map(|actor| actor.hands.iter());
+        self.shoe
+            .discard(cards.flat_map(|hand| hand.cards.iter().cloned()));
+        played?;
+        self.judge_round(&round)?;
+        Ok(true)
     }
-}
 
-fn print_hand_result(result: &HandResult, player_name: &String, coins: &i32) {
-    match result {
-        HandResult::AutoWin => println!("{} : +{:2} coins --> AUTO BLACKJACK!", player_name, coins),
-        HandResult::Win => println!("{} : +{:2} coins --> WON", player_name, coins),
-        HandResult::Draw => println!("{} : +{:2} coins --> DRAW", player_name, coins),
-        HandResult::Loss => println!("{} : -{:2} coins --> LOSS", player_name, -coins),
+    pub fn has_seated_players(&self) -> bool {
+        self.players.iter().any(|player| player.seated)
+    }
+
+    /// Players who can't cover the table minimum either rebuy or leave the table.
+    pub fn offer_rebuys(&mut self) -> Result<(), BlackjackError> {
+        let minimum = self.rules.table_minimum;
+        let buy_in = self.buy_in;
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            if !player.seated || player.bankroll >= minimum {
+                continue;
+            }
+
+            if self.decisions.rebuy(seat, player, buy_in)? {
+                player.rebuy(buy_in);
+                self.observers.emit(GameEvent::PlayerRebought {
+                    player: player.name.clone(),
+                    amount: buy_in,
+                });
+            } else {
+                player.cash_out();
+                self.observers.emit(GameEvent::PlayerCashedOut {
+                    player: player.name.clone(),
+                    bankroll: player.bankroll,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Betting phase, every seated player picks a wager or cashes out. Wagers outside the table
+    /// limits or the player's bankroll are clamped into them, `InvalidInput` when the limits
+    /// themselves are upside down.
+    pub fn take_bets(&mut self) -> Result<Vec<Seat>, BlackjackError> {
+        self.rules.validate()?;
+        let minimum = self.rules.table_minimum;
+        let table_maximum = self.rules.table_maximum;
+        let mut seats = Vec::new();
+        for (player_idx, player) in self.players.iter_mut().enumerate() {
+            if !player.seated {
+                continue;
+            }
+            if player.bankroll < minimum {
+                self.observers.emit(GameEvent::PlayerSatOut {
+                    player: player.name.clone(),
+                });
+                continue;
+            }
+
+            let maximum = table_maximum.min(player.bankroll);
+            match self.decisions.bet(player_idx, player, minimum, maximum)? {
+                Some(bet) => {
+                    let bet = bet.clamp(minimum, maximum);
+                    player.last_bet = bet;
+                    seats.push(Seat {
+                        player_idx,
+                        name: player.name.clone(),
+                        bet,
+                        bankroll: player.bankroll,
+                    });
+                }
+                None => {
+                    player.cash_out();
+                    self.observers.emit(GameEvent::PlayerCashedOut {
+                        player: player.name.clone(),
+                        bankroll: player.bankroll,
+                    });
+                }
+            }
+        }
+        Ok(seats)
     }
 }
 
 impl Game {
-    pub fn judge_round(&mut self, round: &Round) {
+    /// Pays out every hand, results are worked out for all hands before anything is paid.
+    pub fn judge_round(&mut self, round: &Round) -> Result<(), BlackjackError> {
         let i = round.actors.len();
+        let dealer_hand = round.dealer_hand()?;
+        let dealer_natural = dealer_hand.is_natural();
+        // no hole card: the dealer natural shows up after doubles and splits were made
+        let original_bets_only = dealer_natural
+            && round.rules.hole_card == HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly);
+        let mut settlements = Vec::new();
         for actor_idx in 0..(i - 1) {
-            let actor = at!(round.actors, actor_idx);
-            for hand in &actor.hands {
-                let hand_result = calculate_hand_result(hand, round.dealer_hand());
+            let actor = at!(round.actors, actor_idx)?;
+            let player_idx = at!(round.seats, actor_idx)?.player_idx;
+            for (hand_idx, hand) in actor.hands.iter().enumerate() {
+                let insurance = hand.wager.insurance;
+                if insurance > 0 {
+                    let coins = if dealer_natural {
+                        2 * insurance
+                    } else {
+                        -insurance
+                    };
+                    settlements.push((player_idx, None, coins));
+                }
+
+                let hand_result = if hand.even_money {
+                    HandResult::EvenMoney
+                } else if original_bets_only && hand_idx > 0 {
+                    HandResult::Draw
+                } else {
+                    calculate_hand_result(hand, dealer_hand, &round.rules)?
+                };
+                let bet = if original_bets_only {
+                    hand.wager.main
+                } else {
+                    hand.wager.at_risk()
+                };
                 let coins = match hand_result {
-                    HandResult::AutoWin => (3 * at!(round.actor_bets, actor_idx)) / 2,
-                    HandResult::Win => *at!(round.actor_bets, actor_idx),
-                    HandResult::Loss => -at!(round.actor_bets, actor_idx),
+                    HandResult::AutoWin => round.rules.blackjack_payout.pay(bet),
+                    HandResult::Win | HandResult::EvenMoney => bet,
+                    HandResult::Loss => -bet,
                     HandResult::Draw => 0,
+                    HandResult::Surrender => -bet / 2,
                 };
-                print_hand_result(&hand_result, at!(self.player_names, actor_idx), &coins);
-
-                let score = at!(mut self.player_scores, actor_idx);
-                *score += coins
+                settlements.push((player_idx, Some((hand_idx, hand_result)), coins));
             }
         }
+
+        for (player_idx, hand_result, coins) in settlements {
+            let player = at!(mut self.players, player_idx)?;
+            player.settle(coins);
+            let player = player.name.clone();
+            self.observers.emit(match hand_result {
+                Some((hand_idx, result)) => GameEvent::HandSettled {
+                    player,
+                    hand_idx,
+                    result,
+                    coins,
+                },
+                None => GameEvent::InsuranceSettled { player, coins },
+            });
+        }
+        Ok(())
     }
 }
 
-enum HandResult {
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HandResult {
     AutoWin,
     Win,
     Loss,
     Draw,
+    EvenMoney,
+    Surrender,
 }
 
-fn calculate_hand_result(user_hand: &Hand, dealer_hand: &Hand) -> HandResult {
-    match (&user_hand.state, &dealer_hand.state) {
-        (HandState::Finished, HandState::Finished) => match user_hand.sum - dealer_hand.sum {
-            ..0 => HandResult::Loss,
-            0 => HandResult::Draw,
-            1.. => HandResult::Win,
-        },
-        (HandState::Blackjack, _) => {
-            if user_hand.cards.len() == 2 {
-                HandResult::AutoWin
-            } else if matches!(dealer_hand.state, HandState::Blackjack) {
-                HandResult::Draw
+fn calculate_hand_result(
+    user_hand: &Hand,
+    dealer_hand: &Hand,
+    rules: &TableRules,
+) -> Result<HandResult, BlackjackError> {
+    let result = match (&user_hand.state, &dealer_hand.state) {
+        (HandState::Surrendered, HandState::Natural) if rules.surrender == Surrender::Late => {
+            // late surrender is only honored when the dealer has no natural
+            HandResult::Loss
+        }
+        (HandState::Surrendered, _) => HandResult::Surrender,
+        (HandState::Natural, HandState::Natural) => HandResult::Draw,
+        (HandState::Natural, _) => HandResult::AutoWin,
+        (_, HandState::Natural) | (HandState::Bust, _) => HandResult::Loss,
+        (HandState::Finished, HandState::Bust) | (HandState::TwentyOne, HandState::Bust) => {
+            HandResult::Win
+        }
+        (HandState::Finished, HandState::Finished)
+        | (HandState::Finished, HandState::TwentyOne)
+        | (HandState::TwentyOne, HandState::Finished)
+        | (HandState::TwentyOne, HandState::TwentyOne) => {
+            match user_hand.value.total.cmp(&dealer_hand.value.total) {
+                Ordering::Less => HandResult::Loss,
+                Ordering::Equal => HandResult::Draw,
+                Ordering::Greater => HandResult::Win,
+            }
+        }
+        (HandState::Undefined, _) | (_, HandState::Undefined) | (_, HandState::Surrendered) => {
+            return Err(BlackjackError::UnfinishedHand)
+        }
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::cards::card::Card;
+    use crate::cards::deck::Deck;
+    use crate::gameplay::actor::Actor;
+    use crate::gameplay::blackjack::UserAction;
+    use crate::gameplay::event::GameObserver;
+
+    fn hand(cards: &str, split: bool) -> Hand {
+        let mut hand: Hand = cards.parse().unwrap();
+        hand.split = split;
+        hand.state = HandState::Undefined;
+        hand.update_state().unwrap();
+        if matches!(hand.state, HandState::Undefined) {
+            hand.state = HandState::Finished;
+        }
+        hand
+    }
+
+    fn surrendered() -> Hand {
+        let mut hand = hand("10S 6H", false);
+        hand.state = HandState::Surrendered;
+        hand
+    }
+
+    #[test]
+    fn settles_every_player_hand_against_every_dealer_hand() {
+        use HandResult::*;
+
+        let natural = || hand("AS KD", false);
+        let split_21 = || hand("AS KD", true);
+        let hard_21 = || hand("7S 7H 7D", false);
+        let soft_21 = || hand("AS 5H 5D", false);
+        let twenty = || hand("KS QH", false);
+        let eighteen = || hand("9S 9H", false);
+        let bust = || hand("10S 6H 8D", false);
+
+        let dealer_natural = || hand("AH 10C", false);
+        let dealer_21 = || hand("6C 5D KH", false);
+        let dealer_20 = || hand("10C 5D 5H", false);
+        let dealer_18 = || hand("AC 7D", false);
+        let dealer_17 = || hand("10C 7D", false);
+        let dealer_bust = || hand("10C 6D 9H", false);
+
+        #[rustfmt::skip]
+        let table: Vec<(Hand, Hand, HandResult)> = vec![
+            (natural(), dealer_natural(), Draw),
+            (natural(), dealer_21(), AutoWin),
+            (natural(), dealer_20(), AutoWin),
+            (natural(), dealer_18(), AutoWin),
+            (natural(), dealer_17(), AutoWin),
+            (natural(), dealer_bust(), AutoWin),
+
+            (split_21(), dealer_natural(), Loss),
+            (split_21(), dealer_21(), Draw),
+            (split_21(), dealer_20(), Win),
+            (split_21(), dealer_18(), Win),
+            (split_21(), dealer_17(), Win),
+            (split_21(), dealer_bust(), Win),
+
+            (hard_21(), dealer_natural(), Loss),
+            (hard_21(), dealer_21(), Draw),
+            (hard_21(), dealer_20(), Win),
+            (hard_21(), dealer_18(), Win),
+            (hard_21(), dealer_17(), Win),
+            (hard_21(), dealer_bust(), Win),
+
+            (soft_21(), dealer_natural(), Loss),
+            (soft_21(), dealer_21(), Draw),
+            (soft_21(), dealer_20(), Win),
+            (soft_21(), dealer_bust(), Win),
+
+            (twenty(), dealer_natural(), Loss),
+            (twenty(), dealer_21(), Loss),
+            (twenty(), dealer_20(), Draw),
+            (twenty(), dealer_18(), Win),
+            (twenty(), dealer_17(), Win),
+            (twenty(), dealer_bust(), Win),
+
+            (eighteen(), dealer_natural(), Loss),
+            (eighteen(), dealer_21(), Loss),
+            (eighteen(), dealer_20(), Loss),
+            (eighteen(), dealer_18(), Draw),
+            (eighteen(), dealer_17(), Win),
+            (eighteen(), dealer_bust(), Win),
+
+            (bust(), dealer_natural(), Loss),
+            (bust(), dealer_21(), Loss),
+            (bust(), dealer_20(), Loss),
+            (bust(), dealer_18(), Loss),
+            (bust(), dealer_17(), Loss),
+            (bust(), dealer_bust(), Loss),
+        ];
+
+        let rules = TableRules::default();
+        for (player, dealer, expected) in table {
+            assert_eq!(
+                calculate_hand_result(&player, &dealer, &rules).unwrap(),
+                expected,
+                "{} vs dealer {}",
+                player,
+                dealer
+            );
+        }
+    }
+
+    #[test]
+    fn split_and_multi_card_21_are_not_naturals() {
+        assert!(matches!(hand("AS KD", false).state, HandState::Natural));
+        assert!(matches!(hand("AS KD", true).state, HandState::TwentyOne));
+        assert!(matches!(
+            hand("7S 7H 7D", false).state,
+            HandState::TwentyOne
+        ));
+    }
+
+    #[test]
+    fn settles_surrender_against_dealer_natural() {
+        let mut rules = TableRules {
+            surrender: Surrender::Late,
+            ..TableRules::default()
+        };
+        let dealer_natural = hand("AH 10C", false);
+        let dealer_20 = hand("10C QD", false);
+        assert_eq!(
+            calculate_hand_result(&surrendered(), &dealer_natural, &rules).unwrap(),
+            HandResult::Loss
+        );
+        assert_eq!(
+            calculate_hand_result(&surrendered(), &dealer_20, &rules).unwrap(),
+            HandResult::Surrender
+        );
+
+        rules.surrender = Surrender::Early;
+        assert_eq!(
+            calculate_hand_result(&surrendered(), &dealer_natural, &rules).unwrap(),
+            HandResult::Surrender
+        );
+        assert_eq!(
+            calculate_hand_result(&surrendered(), &dealer_20, &rules).unwrap(),
+            HandResult::Surrender
+        );
+    }
+
+    /// Plays the scripted bets and actions in order, bets the minimum once the bets run out.
+    /// Running out of actions is `InputClosed`.
+    #[derive(Default)]
+    struct Script {
+        bets: VecDeque<Option<i32>>,
+        /// Rebuy answer per seat, no by default.
+        rebuys: Vec<bool>,
+        /// Insurance asked for per seat, none by default.
+        insurance: Vec<i32>,
+        /// Even money answer per seat, no by default.
+        even_money: Vec<bool>,
+        actions: VecDeque<UserAction>,
+    }
+
+    impl DecisionProvider for Script {
+        fn bet(
+            &mut self,
+            _seat: usize,
+            _player: &Player,
+            minimum: i32,
+            _maximum: i32,
+        ) -> Result<Option<i32>, BlackjackError> {
+            Ok(self.bets.pop_front().unwrap_or(Some(minimum)))
+        }
+
+        fn rebuy(
+            &mut self,
+            seat: usize,
+            _player: &Player,
+            _buy_in: i32,
+        ) -> Result<bool, BlackjackError> {
+            Ok(self.rebuys.get(seat).copied().unwrap_or(false))
+        }
+
+        fn insurance(
+            &mut self,
+            seat: usize,
+            _actor: &Actor,
+            _maximum: i32,
+        ) -> Result<i32, BlackjackError> {
+            Ok(self.insurance.get(seat).copied().unwrap_or(0))
+        }
+
+        fn even_money(&mut self, seat: usize, _actor: &Actor) -> Result<bool, BlackjackError> {
+            Ok(self.even_money.get(seat).copied().unwrap_or(false))
+        }
+
+        fn early_surrender(
+            &mut self,
+            _seat: usize,
+            _actor: &Actor,
+            _dealer_up_card: &Card,
+        ) -> Result<bool, BlackjackError> {
+            Ok(false)
+        }
+
+        fn action(
+            &mut self,
+            _seat: usize,
+            _actor: &Actor,
+            _hand_idx: usize,
+            _dealer_up_card: &Card,
+            _actions: &[UserAction],
+        ) -> Result<UserAction, BlackjackError> {
+            self.actions.pop_front().ok_or(BlackjackError::InputClosed)
+        }
+
+        fn another_round(&mut self) -> Result<bool, BlackjackError> {
+            Ok(false)
+        }
+    }
+
+    /// `(player, hand_idx, result, coins)` of a settled hand.
+    type Settled = (String, usize, HandResult, i32);
+
+    #[derive(Default)]
+    struct Events(Vec<GameEvent>);
+
+    impl Events {
+        fn settled(&self) -> Vec<Settled> {
+            let mut settled = Vec::new();
+            for event in self.0.iter() {
+                if let GameEvent::HandSettled {
+                    player,
+                    hand_idx,
+                    result,
+                    coins,
+                } = event
+                {
+                    settled.push((player.clone(), *hand_idx, *result, *coins));
+                }
+            }
+            settled
+        }
+
+        /// `(player, coins)` of every settled insurance bet.
+        fn insurance(&self) -> Vec<(String, i32)> {
+            let mut insurance = Vec::new();
+            for event in self.0.iter() {
+                if let GameEvent::InsuranceSettled { player, coins } = event {
+                    insurance.push((player.clone(), *coins));
+                }
+            }
+            insurance
+        }
+    }
+
+    impl GameObserver for Events {
+        fn on_event(&mut self, event: &GameEvent) {
+            self.0.push(event.clone());
+        }
+    }
+
+    /// Players named `P1`, `P2`... with the bankrolls, 100 coins per buy-in.
+    fn table(rules: TableRules, bankrolls: &[i32], script: Script) -> (Game, Rc<RefCell<Events>>) {
+        let events = Rc::new(RefCell::new(Events::default()));
+        let mut game = Game {
+            players: bankrolls
+                .iter()
+                .enumerate()
+                .map(|(idx, bankroll)| Player::new(format!("P{}", idx + 1), *bankroll, 10))
+                .collect(),
+            buy_in: 100,
+            shoe: Shoe::with_seed(rules.number_of_decks, rules.penetration, 1).unwrap(),
+            rules,
+            decisions: Box::new(script),
+            observers: Observers::default(),
+        };
+        game.observers.register(Box::new(events.clone()));
+        (game, events)
+    }
+
+    /// Players bet 10 out of 1000 each, cards are dealt in the order of `deck`: two per
+    /// player, the dealer's up-card, the hole card, then hits.
+    fn scripted_round(
+        rules: TableRules,
+        players: usize,
+        deck: &str,
+        actions: &[UserAction],
+    ) -> Result<(Game, Vec<Settled>), BlackjackError> {
+        let script = Script {
+            actions: actions.iter().copied().collect(),
+            ..Script::default()
+        };
+        let (game, events) = play_script(rules, players, deck, script)?;
+        let settled = events.borrow().settled();
+        Ok((game, settled))
+    }
+
+    /// `scripted_round` with every answer scripted.
+    fn play_script(
+        rules: TableRules,
+        players: usize,
+        deck: &str,
+        script: Script,
+    ) -> Result<(Game, Rc<RefCell<Events>>), BlackjackError> {
+        let (mut game, events) = table(rules, &vec![1000; players], script);
+        game.shoe.stack(deck.parse()?);
+        assert!(game.play_round()?);
+        Ok((game, events))
+    }
+
+    fn settled(player: &str, hand_idx: usize, result: HandResult, coins: i32) -> Settled {
+        (player.to_string(), hand_idx, result, coins)
+    }
+
+    #[test]
+    fn splits_and_resplits_pairs() {
+        use UserAction::{Double, Split, Stay};
+
+        // 8 8 vs 10 7, the first split draws another 8, the last hand doubles 11 into 20
+        let deck = "8S 8H 10C 7D 8D 3C 10S 10H 9S";
+        let actions = [Split, Split, Stay, Stay, Double];
+        let (game, results) = scripted_round(TableRules::default(), 1, deck, &actions).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                settled("P1", 0, HandResult::Win, 10),
+                settled("P1", 1, HandResult::Win, 10),
+                settled("P1", 2, HandResult::Win, 20),
+            ]
+        );
+        assert_eq!(game.players[0].bankroll, 1040);
+
+        let two_hands = TableRules {
+            max_split_hands: 2,
+            ..TableRules::default()
+        };
+        assert!(matches!(
+            scripted_round(two_hands, 1, deck, &[Split, Split]),
+            Err(BlackjackError::InvalidAction(Split))
+        ));
+    }
+
+    #[test]
+    fn split_aces_get_one_card_each() {
+        // A A vs 9 7, the split hands are not asked again and the dealer busts on the next card
+        let deck = "AS AH 9C 7D KD 5C 10S";
+        let (game, results) =
+            scripted_round(TableRules::default(), 1, deck, &[UserAction::Split]).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                settled("P1", 0, HandResult::Win, 10),
+                settled("P1", 1, HandResult::Win, 10),
+            ]
+        );
+        assert_eq!(game.players[0].bankroll, 1020);
+    }
+
+    #[test]
+    fn dealer_natural_ends_the_round_after_the_peek() {
+        // nobody is asked for an action, the script has none
+        let deck = "10S 9H AS KH AC KD";
+        let (game, results) = scripted_round(TableRules::default(), 2, deck, &[]).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                settled("P1", 0, HandResult::Loss, -10),
+                settled("P2", 0, HandResult::Draw, 0),
+            ]
+        );
+        assert_eq!(game.players[0].bankroll, 990);
+        assert_eq!(game.players[1].bankroll, 1000);
+    }
+
+    #[test]
+    fn hole_card_is_only_told_when_revealed() {
+        let script = Script {
+            actions: vec![UserAction::Stay].into(),
+            ..Script::default()
+        };
+        let (mut game, events) = table(TableRules::default(), &[1000], script);
+        game.shoe.stack("10S 9H 7C KD".parse().unwrap());
+        assert!(game.play_round().unwrap());
+
+        let events = &events.borrow().0;
+        let dealt = events
+            .iter()
+            .position(|event| matches!(event, GameEvent::HoleCardDealt { actor_idx: 1 }))
+            .unwrap();
+        let revealed = events
+            .iter()
+            .position(|event| matches!(event, GameEvent::HoleCardRevealed { .. }))
+            .unwrap();
+        assert!(dealt < revealed);
+        let hole_card: Card = "KD".parse().unwrap();
+        assert!(!events[..revealed].iter().any(|event| matches!(
+            event,
+            GameEvent::CardDealt { card, .. } if *card == hole_card
+        )));
+        assert!(matches!(
+            &events[revealed],
+            GameEvent::HoleCardRevealed { card } if *card == hole_card
+        ));
+    }
+
+    #[test]
+    fn runs_out_mid_round_without_dealing_the_table_twice() {
+        let script = || Script {
+            actions: vec![UserAction::Stay].into(),
+            ..Script::default()
+        };
+        let (mut game, _) = table(TableRules::default(), &[1000], script());
+        game.shoe.stack("10S 9H 10C".parse().unwrap());
+        assert!(matches!(game.play_round(), Err(BlackjackError::OutOfCards)));
+        assert_eq!(game.players[0].bankroll, 1000);
+
+        let (mut game, events) = table(TableRules::default(), &[1000], script());
+        game.shoe.stack("10S 9H 10C".parse().unwrap());
+        let discards: Deck = "7D".parse().unwrap();
+        game.shoe.discard(discards.cards);
+        assert!(game.play_round().unwrap());
+        assert_eq!(
+            events.borrow().settled(),
+            vec![settled("P1", 0, HandResult::Win, 10)]
+        );
+        assert!(events
+            .borrow()
+            .0
+            .iter()
+            .any(|event| matches!(event, GameEvent::DiscardsShuffled { cards: 1 })));
+        // the round's cards are the only discards left
+        assert!(game.shoe.shuffle_discards());
+        assert_eq!(game.shoe.remaining_cards(), 4);
+    }
+
+    #[test]
+    fn insurance_pays_two_to_one_against_a_natural() {
+        let insured = Script {
+            insurance: vec![5],
+            ..Script::default()
+        };
+        let (game, events) =
+            play_script(TableRules::default(), 1, "10S 9H AC KD", insured).unwrap();
+        let events = events.borrow();
+        assert_eq!(
+            events.settled(),
+            vec![settled("P1", 0, HandResult::Loss, -10)]
+        );
+        assert_eq!(events.insurance(), vec![("P1".to_string(), 10)]);
+        assert_eq!(game.players[0].bankroll, 1000);
+    }
+
+    #[test]
+    fn insurance_is_lost_without_a_natural() {
+        let insured = Script {
+            insurance: vec![5],
+            actions: vec![UserAction::Stay].into(),
+            ..Script::default()
+        };
+        let (game, events) =
+            play_script(TableRules::default(), 1, "10S 9H AC 7D", insured).unwrap();
+        let events = events.borrow();
+        assert_eq!(
+            events.settled(),
+            vec![settled("P1", 0, HandResult::Win, 10)]
+        );
+        assert_eq!(events.insurance(), vec![("P1".to_string(), -5)]);
+        assert_eq!(game.players[0].bankroll, 1005);
+    }
+
+    #[test]
+    fn even_money_pays_one_to_one_against_a_natural() {
+        let even_money = Script {
+            even_money: vec![true],
+            ..Script::default()
+        };
+        let (game, events) =
+            play_script(TableRules::default(), 1, "AS KH AC KD", even_money).unwrap();
+        let events = events.borrow();
+        assert_eq!(
+            events.settled(),
+            vec![settled("P1", 0, HandResult::EvenMoney, 10)]
+        );
+        assert!(events.insurance().is_empty());
+        assert_eq!(game.players[0].bankroll, 1010);
+    }
+
+    #[test]
+    fn insurance_is_clamped_to_half_the_bet() {
+        for (asked, taken) in [(50, 5), (-20, 0)] {
+            let insured = Script {
+                insurance: vec![asked],
+                ..Script::default()
+            };
+            let (game, events) =
+                play_script(TableRules::default(), 1, "10S 9H AC KD", insured).unwrap();
+            let events = events.borrow();
+            let amounts: Vec<i32> = events
+                .0
+                .iter()
+                .filter_map(|event| match event {
+                    GameEvent::InsuranceTaken { amount, .. } => Some(*amount),
+                    _ => None,
+                })
+                .collect();
+            if taken > 0 {
+                assert_eq!(amounts, vec![taken]);
+                assert_eq!(events.insurance(), vec![("P1".to_string(), 2 * taken)]);
             } else {
-                HandResult::Win
+                assert!(amounts.is_empty());
+                assert!(events.insurance().is_empty());
             }
+            assert_eq!(game.players[0].bankroll, 990 + 2 * taken);
+        }
+    }
+
+    fn no_hole_card(loss: NoHoleCardLoss) -> TableRules {
+        TableRules {
+            hole_card: HoleCardRule::NoHoleCard(loss),
+            ..TableRules::default()
         }
-        (HandState::Finished, HandState::Bust) => HandResult::Win,
-        (HandState::Bust, _) | (_, HandState::Blackjack) => HandResult::Loss,
-        (HandState::Undefined, _) | (_, HandState::Undefined) => unreachable!(),
+    }
+
+    #[test]
+    fn obo_dealer_draws_after_the_players_and_pushes_split_hands() {
+        use UserAction::{Split, Stay};
+
+        // 8 8 vs 10, both hands stand on 18 before the dealer's ace shows up
+        let deck = "8S 8H 10C 10D 10H AS";
+        let script = Script {
+            actions: vec![Split, Stay, Stay].into(),
+            ..Script::default()
+        };
+        let rules = no_hole_card(NoHoleCardLoss::OriginalBetsOnly);
+        let (game, events) = play_script(rules, 1, deck, script).unwrap();
+        let events = events.borrow();
+        assert!(!events
+            .0
+            .iter()
+            .any(|event| matches!(event, GameEvent::HoleCardDealt { .. })));
+        let last_action = events
+            .0
+            .iter()
+            .rposition(|event| matches!(event, GameEvent::ActionTaken { actor_idx: 0, .. }))
+            .unwrap();
+        let ace: Card = "AS".parse().unwrap();
+        let dealer_ace = events
+            .0
+            .iter()
+            .position(|event| {
+                matches!(event, GameEvent::CardDealt { actor_idx: 1, card, .. } if *card == ace)
+            })
+            .unwrap();
+        assert!(last_action < dealer_ace);
+        assert_eq!(
+            events.settled(),
+            vec![
+                settled("P1", 0, HandResult::Loss, -10),
+                settled("P1", 1, HandResult::Draw, 0),
+            ]
+        );
+        assert_eq!(game.players[0].bankroll, 990);
+    }
+
+    #[test]
+    fn enhc_dealer_natural_takes_split_hands() {
+        use UserAction::{Split, Stay};
+
+        let deck = "8S 8H 10C 10D 10H AS";
+        let rules = no_hole_card(NoHoleCardLoss::AllBets);
+        let (game, results) = scripted_round(rules, 1, deck, &[Split, Stay, Stay]).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                settled("P1", 0, HandResult::Loss, -10),
+                settled("P1", 1, HandResult::Loss, -10),
+            ]
+        );
+        assert_eq!(game.players[0].bankroll, 980);
+    }
+
+    #[test]
+    fn dealer_natural_after_a_double_takes_the_original_bet_or_all() {
+        // 11 vs 10 doubles into 20, the dealer's second card is an ace
+        let deck = "6S 5H 10C 9D AS";
+        let double = [UserAction::Double];
+
+        let rules = no_hole_card(NoHoleCardLoss::OriginalBetsOnly);
+        let (game, results) = scripted_round(rules, 1, deck, &double).unwrap();
+        assert_eq!(results, vec![settled("P1", 0, HandResult::Loss, -10)]);
+        assert_eq!(game.players[0].bankroll, 990);
+
+        let rules = no_hole_card(NoHoleCardLoss::AllBets);
+        let (game, results) = scripted_round(rules, 1, deck, &double).unwrap();
+        assert_eq!(results, vec![settled("P1", 0, HandResult::Loss, -20)]);
+        assert_eq!(game.players[0].bankroll, 980);
+    }
+
+    #[test]
+    fn dealer_hits_soft_17_when_the_rules_say_so() {
+        // 18 vs A 6, the next card makes the dealer soft 19
+        let deck = "10S 8H 6C AD 2S";
+        let stay = [UserAction::Stay];
+        let (_, results) = scripted_round(TableRules::default(), 1, deck, &stay).unwrap();
+        assert_eq!(results, vec![settled("P1", 0, HandResult::Win, 10)]);
+
+        let h17 = TableRules {
+            dealer_hits_soft_17: true,
+            ..TableRules::default()
+        };
+        let (_, results) = scripted_round(h17, 1, deck, &stay).unwrap();
+        assert_eq!(results, vec![settled("P1", 0, HandResult::Loss, -10)]);
+    }
+
+    #[test]
+    fn busted_hand_loses_whatever_the_dealer_draws() {
+        // 16 vs 10 6, the player busts and the dealer would have busted too
+        let deck = "10S 6H 10C 6D 10H 10D";
+        let hit = [UserAction::Hit];
+        let (game, results) = scripted_round(TableRules::default(), 1, deck, &hit).unwrap();
+        assert_eq!(results, vec![settled("P1", 0, HandResult::Loss, -10)]);
+        assert_eq!(game.players[0].bankroll, 990);
+    }
+
+    #[test]
+    fn doubled_hand_wins_twice_the_bet() {
+        // 11 vs 10 6, the double draws a 10 and the dealer busts
+        let deck = "6S 5H 10C 6D 10S 10H";
+        let double = [UserAction::Double];
+        let (game, results) = scripted_round(TableRules::default(), 1, deck, &double).unwrap();
+        assert_eq!(results, vec![settled("P1", 0, HandResult::Win, 20)]);
+        assert_eq!(game.players[0].bankroll, 1020);
+    }
+
+    #[test]
+    fn clamps_every_bet_into_the_table_limits_and_bankroll() {
+        let script = Script {
+            bets: vec![Some(5), Some(100), Some(900), Some(0), None, Some(40)]
+                .into_iter()
+                .collect(),
+            ..Script::default()
+        };
+        let (mut game, events) = table(
+            TableRules::default(),
+            &[1000, 50, 1000, 1000, 1000, 1000],
+            script,
+        );
+        let seats = game.take_bets().unwrap();
+        let bets: Vec<(usize, i32)> = seats
+            .iter()
+            .map(|seat| (seat.player_idx, seat.bet))
+            .collect();
+        assert_eq!(bets, [(0, 10), (1, 50), (2, 500), (3, 10), (5, 40)]);
+        assert_eq!(game.players[2].last_bet, 500);
+
+        assert!(!game.players[4].seated);
+        assert!(matches!(
+            &events.borrow().0[..],
+            [GameEvent::PlayerCashedOut { player, bankroll: 1000 }] if player == "P5"
+        ));
+    }
+
+    #[test]
+    fn refuses_upside_down_table_limits() {
+        let rules = TableRules {
+            table_minimum: 100,
+            table_maximum: 50,
+            ..TableRules::default()
+        };
+        let (mut game, _) = table(rules, &[1000], Script::default());
+        assert!(matches!(
+            game.take_bets(),
+            Err(BlackjackError::InvalidInput(_))
+        ));
+        assert_eq!(game.players[0].bankroll, 1000);
+    }
+
+    #[test]
+    fn broke_players_rebuy_or_cash_out() {
+        let script = Script {
+            rebuys: vec![true, false],
+            ..Script::default()
+        };
+        let (mut game, events) = table(TableRules::default(), &[5, 5, 10], script);
+        game.offer_rebuys().unwrap();
+
+        let player = &game.players[0];
+        assert_eq!(
+            (player.bankroll, player.buy_ins, player.peak),
+            (105, 105, 105)
+        );
+        assert_eq!(player.net_result(), 0);
+        assert!(player.seated);
+        assert!(!game.players[1].seated);
+        assert_eq!(game.players[2].bankroll, 10);
+        assert!(game.players[2].seated);
+
+        let events = &events.borrow().0;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            GameEvent::PlayerRebought { player, amount: 100 } if player == "P1"
+        ));
+        assert!(matches!(
+            &events[1],
+            GameEvent::PlayerCashedOut { player, bankroll: 5 } if player == "P2"
+        ));
+    }
+
+    #[test]
+    fn players_below_the_minimum_sit_out_the_bets() {
+        let (mut game, events) = table(TableRules::default(), &[5, 1000], Script::default());
+        let seats = game.take_bets().unwrap();
+        assert_eq!(seats.len(), 1);
+        assert_eq!(seats[0].player_idx, 1);
+        assert!(game.players[0].seated);
+        assert!(matches!(
+            &events.borrow().0[..],
+            [GameEvent::PlayerSatOut { player }] if player == "P1"
+        ));
+    }
+
+    #[test]
+    fn session_ends_once_everyone_cashed_out() {
+        let script = Script {
+            bets: vec![None].into_iter().collect(),
+            ..Script::default()
+        };
+        let (mut game, _) = table(TableRules::default(), &[1000], script);
+        assert!(!game.play_round().unwrap());
+        assert!(!game.has_seated_players());
+        assert!(!game.play_round().unwrap());
+
+        // nobody can cover the minimum and nobody rebuys
+        let (mut game, _) = table(TableRules::default(), &[5, 5], Script::default());
+        assert!(!game.play_round().unwrap());
+        assert!(game.players.iter().all(|player| !player.seated));
     }
 }
@@ -1,49 +1,168 @@
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
-use crate::{at, take_stdin_key};
-use crate::cards::card::{Card, CardSymbol};
+use crate::at;
+use crate::cards::card::{Card, CardSymbol, ParseCardError};
+use crate::error::BlackjackError;
 use crate::gameplay::blackjack::{blackjack_card_value, UserAction};
+use crate::gameplay::rules::{DoubleRule, Surrender, TableRules};
+use crate::gameplay::wager::Wager;
 
 pub enum HandState {
     Undefined,
     Finished,
     Bust,
-    Blackjack,
+    /// Two card 21 on an unsplit hand, beats any other 21.
+    Natural,
+    /// Any other 21, finishes the hand.
+    TwentyOne,
+    Surrendered,
 }
 
 impl HandState {
     pub fn from_value(value: i32) -> HandState {
         if value > 21 {
-            return HandState::Bust;
+            HandState::Bust
+        } else if value == 21 {
+            HandState::TwentyOne
+        } else {
+            HandState::Undefined
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HandValue {
+    pub total: i32,
+    /// An ace is still counted as 11 in `total`.
+    pub soft: bool,
+}
+
+impl HandValue {
+    /// `ParseCardError::Card` for a joker, it has no value in blackjack.
+    pub fn from_cards<'a>(cards: impl Iterator<Item = &'a Card>) -> Result<Self, ParseCardError> {
+        let mut total = 0;
+        let mut number_of_aces = 0;
+        for card in cards {
+            if card.value == CardSymbol::Joker {
+                return Err(ParseCardError::Card(card.to_string()));
+            }
+            total += blackjack_card_value(&card.value);
+            if card.value == CardSymbol::Ace {
+                number_of_aces += 1;
+            }
         }
-        if value == 21 {
-            return HandState::Blackjack;
+
+        while number_of_aces > 0 {
+            if total <= 21 {
+                break;
+            }
+            total -= 10;
+            number_of_aces -= 1;
         }
-        if value < 21 {
-            return HandState::Undefined;
+
+        Ok(HandValue {
+            total,
+            soft: number_of_aces > 0 && total <= 21,
+        })
+    }
+}
+
+/// `17` or `soft 17`.
+impl fmt::Display for HandValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.soft {
+            write!(f, "soft {}", self.total)
+        } else {
+            write!(f, "{}", self.total)
         }
-        unreachable!()
     }
 }
 
 pub struct Hand {
     pub state: HandState,
-    pub sum: i32,
+    pub value: HandValue,
+    /// The hand was created by splitting a pair.
+    pub split: bool,
+    pub wager: Wager,
+    /// A natural paid 1:1 up front while the dealer shows an ace.
+    pub even_money: bool,
     pub cards: VecDeque<Card>,
 }
 
 impl Hand {
-    fn splitable(&self) -> bool {
-        if self.cards.len() == 2 && self.card_at(0).value == self.card_at(1).value {
-            return true;
+    /// Symbol of a two card pair.
+    pub fn pair(&self) -> Option<CardSymbol> {
+        match (self.cards.front(), self.cards.back()) {
+            (Some(first), Some(second)) if self.cards.len() == 2 && first.value == second.value => {
+                Some(first.value)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_ten_valued_pair(&self) -> bool {
+        self.cards.len() == 2
+            && self
+                .cards
+                .iter()
+                .all(|card| blackjack_card_value(&card.value) == 10)
+    }
+
+    fn splitable(&self, rules: &TableRules, number_of_hands: usize) -> bool {
+        if self.pair().is_none() && !(rules.split_unlike_tens && self.is_ten_valued_pair()) {
+            return false;
+        }
+        if number_of_hands >= rules.max_split_hands {
+            return false;
+        }
+        if self.is_split_aces() && !rules.resplit_aces {
+            return false;
         }
-        false
+        true
+    }
+
+    fn doubleable(&self, rules: &TableRules) -> bool {
+        if self.cards.len() != 2 || (self.split && !rules.double_after_split) {
+            return false;
+        }
+        match rules.double {
+            DoubleRule::AnyTwoCards => true,
+            DoubleRule::NineToEleven => (9..=11).contains(&self.value.total),
+            DoubleRule::TenToEleven => (10..=11).contains(&self.value.total),
+            DoubleRule::NotAllowed => false,
+        }
+    }
+
+    /// Surrender is only offered as the first decision on an unsplit hand.
+    fn surrenderable(&self, rules: &TableRules) -> bool {
+        rules.surrender != Surrender::NotAllowed && self.cards.len() == 2 && !self.split
+    }
+
+    /// Two card 21 on an unsplit hand.
+    pub fn is_natural(&self) -> bool {
+        self.cards.len() == 2 && !self.split && self.value.total == 21
+    }
+
+    pub fn is_split_aces(&self) -> bool {
+        self.split
+            && self
+                .cards
+                .front()
+                .map_or(false, |card| card.value == CardSymbol::Ace)
     }
 
     pub fn new() -> Self {
         Hand {
             cards: VecDeque::new(),
-            sum: 0,
+            value: HandValue {
+                total: 0,
+                soft: false,
+            },
+            split: false,
+            wager: Wager::default(),
+            even_money: false,
             state: HandState::Undefined,
         }
     }
@@ -51,16 +170,25 @@ impl Hand {
     pub fn from_cards(cards: Vec<Card>) -> Self {
         Hand {
             cards: VecDeque::from(cards),
-            sum: 0,
+            value: HandValue {
+                total: 0,
+                soft: false,
+            },
+            split: false,
+            wager: Wager::default(),
+            even_money: false,
             state: HandState::Undefined,
         }
     }
 
-    pub fn describe(&self) -> String {
+    /// Hidden cards show as `X`, `ParseCardError::Card` for a joker.
+    pub fn describe(&self) -> Result<String, ParseCardError> {
         let mut strings = Vec::new();
         let mut fully_revealed = true;
         for card in self.cards.iter() {
-            assert!(card.value != CardSymbol::Joker);
+            if card.value == CardSymbol::Joker {
+                return Err(ParseCardError::Card(card.to_string()));
+            }
             if card.is_revealed() {
                 strings.push(card.value.to_str().to_string());
             } else {
@@ -71,19 +199,22 @@ impl Hand {
 
         let mut hand_str = strings.join(" + ");
         if fully_revealed {
-            hand_str.push_str(format!(" = {}", self.sum).as_str());
+            hand_str.push_str(format!(" = {}", self.value).as_str());
         } else {
             hand_str.push_str(" = ?");
         }
+        if self.wager.is_doubled() {
+            hand_str.push_str(" (doubled)");
+        }
 
-        hand_str
+        Ok(hand_str)
     }
 
-    pub fn card_at(&self, at: usize) -> &Card {
+    pub fn card_at(&self, at: usize) -> Result<&Card, BlackjackError> {
         at!(self.cards, at)
     }
 
-    pub fn card_at_mut(&mut self, at: usize) -> &mut Card {
+    pub fn card_at_mut(&mut self, at: usize) -> Result<&mut Card, BlackjackError> {
         at!(mut self.cards, at)
     }
 
@@ -91,84 +222,137 @@ impl Hand {
         self.cards.push_back(card)
     }
 
-    pub fn split(&mut self, card_0_1: Card, card_1_1: Card) -> Hand {
-        let card_1_0 = self.cards.pop_back().unwrap();
+    pub fn split(&mut self, card_0_1: Card, card_1_1: Card) -> Result<Hand, BlackjackError> {
+        if self.cards.len() != 2 {
+            return Err(BlackjackError::InvalidAction(UserAction::Split));
+        }
+        let card_1_0 = self.cards.pop_back().ok_or(BlackjackError::OutOfCards)?;
         self.cards.push_back(card_0_1);
-        Hand::from_cards(vec![card_1_0, card_1_1])
+        self.split = true;
+        let mut hand = Hand::from_cards(vec![card_1_0, card_1_1]);
+        hand.split = true;
+        hand.wager = Wager::new(self.wager.main);
+        Ok(hand)
     }
 
-    pub(crate) fn update_state(&mut self) {
-        let mut sum = 0;
-        let mut number_of_aces = 0;
-        for card in self.cards.iter() {
-            assert!(card.value != CardSymbol::Joker);
-            sum += blackjack_card_value(&card.value);
-            if card.value == CardSymbol::Ace {
-                number_of_aces += 1;
-            }
+    pub fn update_state(&mut self) -> Result<(), ParseCardError> {
+        self.value = HandValue::from_cards(self.cards.iter())?;
+
+        if matches!(self.state, HandState::Undefined) {
+            self.state = if self.is_natural() {
+                HandState::Natural
+            } else {
+                HandState::from_value(self.value.total)
+            };
         }
+        Ok(())
+    }
 
-        while number_of_aces > 0 {
-            if sum <= 21 {
-                break;
+    pub fn available_actions(&self, rules: &TableRules, number_of_hands: usize) -> Vec<UserAction> {
+        let mut actions = vec![UserAction::Stay];
+        if !self.is_split_aces() || rules.hit_split_aces || self.cards.len() > 2 {
+            actions.push(UserAction::Hit);
+            if self.doubleable(rules) {
+                actions.push(UserAction::Double);
             }
-            sum -= 10;
-            number_of_aces -= 1;
         }
+        if self.splitable(rules, number_of_hands) {
+            actions.push(UserAction::Split);
+        }
+        if self.surrenderable(rules) {
+            actions.push(UserAction::Surrender);
+        }
+        actions
+    }
+}
 
-        self.sum = sum;
+impl Default for Hand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        if matches!(self.state, HandState::Undefined) {
-            self.state = HandState::from_value(sum);
-        }
-    }
-
-    pub(crate) fn prompt_user_action(
-        &self,
-        actor_name: &String,
-        hand_idx: usize,
-    ) -> UserAction {
-        loop {
-            if self.splitable() {
-                let prompt = format!(
-                    "{}:{} STAY/HIT/SPLIT? [s/h/x]:",
-                    actor_name,
-                    hand_idx + 1
-                );
-                match take_stdin_key!(prompt, 's', 'h', 'x') {
-                    'h' => {
-                        return UserAction::Hit;
-                    }
-                    's' => {
-                        return UserAction::Stay;
-                    }
-                    'x' => {
-                        return UserAction::Split;
-                    }
-                    _ => {
-                        println!("Invalid command.");
-                        continue;
-                    }
-                }
-            } else {
-                let prompt = format!(
-                    "{}:{} STAY/HIT? [s/h]:",
-                    actor_name,
-                    hand_idx + 1
-                );
-                match take_stdin_key!(prompt, 's', 'h') {
-                    'h' => {
-                        return UserAction::Hit;
-                    }
-                    's' => {
-                        return UserAction::Stay;
-                    }
-                    _ => {
-                        println!("Invalid command.");
-                        continue;
-                    }
-                }
+/// Cards in brackets, e.g. `[AS KD]`.
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards: Vec<String> = self.cards.iter().map(|card| card.to_string()).collect();
+        write!(f, "[{}]", cards.join(" "))
+    }
+}
+
+impl FromStr for Hand {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let inner = match (trimmed.starts_with('['), trimmed.ends_with(']')) {
+            (true, true) => &trimmed[1..trimmed.len() - 1],
+            (false, false) => trimmed,
+            _ => return Err(ParseCardError::Hand(s.to_string())),
+        };
+
+        let cards = inner
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<Card>, ParseCardError>>()?;
+
+        let mut hand = Hand::from_cards(cards);
+        hand.update_state()?;
+        Ok(hand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::card::Suit;
+
+    #[test]
+    fn reads_back_what_it_prints() {
+        let hand: Hand = "[AS 10H 5D]".parse().unwrap();
+        assert_eq!(hand.to_string(), "[AS 10H 5D]");
+        assert_eq!(
+            hand.value,
+            HandValue {
+                total: 16,
+                soft: false
             }
-        }
+        );
+        assert_eq!(hand.to_string().parse::<Hand>().unwrap().cards, hand.cards);
+
+        let hand: Hand = " AS 5D ".parse().unwrap();
+        assert_eq!(hand.value.to_string(), "soft 16");
+        assert!("[]".parse::<Hand>().unwrap().cards.is_empty());
+    }
+
+    #[test]
+    fn rejects_broken_hands() {
+        let error = |s: &str| s.parse::<Hand>().err();
+        assert_eq!(
+            error("[AS KD"),
+            Some(ParseCardError::Hand("[AS KD".to_string()))
+        );
+        assert_eq!(
+            error("AS KD]"),
+            Some(ParseCardError::Hand("AS KD]".to_string()))
+        );
+        assert_eq!(
+            error("XS 5H"),
+            Some(ParseCardError::Symbol("X".to_string()))
+        );
+        assert_eq!(
+            error("AS,KD"),
+            Some(ParseCardError::Symbol("AS,K".to_string()))
+        );
+    }
+
+    #[test]
+    fn refuses_to_value_a_joker() {
+        let mut hand: Hand = "AS".parse().unwrap();
+        hand.deal_card(Card::new(Suit::Heart, CardSymbol::Joker));
+        let joker = || Some(ParseCardError::Card("XH".to_string()));
+        assert_eq!(hand.update_state().err(), joker());
+        assert_eq!(hand.describe().err(), joker());
+        assert_eq!(HandValue::from_cards(hand.cards.iter()).err(), joker());
     }
 }
@@ -1,5 +1,11 @@
-mod actor;
-mod blackjack;
+pub mod actor;
+pub mod blackjack;
+pub mod dealer;
+pub mod decision;
+pub mod event;
 pub mod game;
 pub mod hand;
+pub mod player;
 pub mod round;
+pub mod rules;
+pub mod wager;
@@ -0,0 +1,44 @@
+/// A player's session at the table, from the first buy-in until cashing out.
+pub struct Player {
+    pub name: String,
+    pub bankroll: i32,
+    pub last_bet: i32,
+    /// Total coins bought in, first buy-in included.
+    pub buy_ins: i32,
+    /// Highest bankroll reached during the session.
+    pub peak: i32,
+    /// Still at the table, false once cashed out.
+    pub seated: bool,
+}
+
+impl Player {
+    pub fn new(name: String, buy_in: i32, first_bet: i32) -> Self {
+        Self {
+            name,
+            bankroll: buy_in,
+            last_bet: first_bet,
+            buy_ins: buy_in,
+            peak: buy_in,
+            seated: true,
+        }
+    }
+
+    pub fn rebuy(&mut self, amount: i32) {
+        self.buy_ins += amount;
+        self.bankroll += amount;
+        self.peak = self.peak.max(self.bankroll);
+    }
+
+    pub fn settle(&mut self, coins: i32) {
+        self.bankroll += coins;
+        self.peak = self.peak.max(self.bankroll);
+    }
+
+    pub fn cash_out(&mut self) {
+        self.seated = false;
+    }
+
+    pub fn net_result(&self) -> i32 {
+        self.bankroll - self.buy_ins
+    }
+}
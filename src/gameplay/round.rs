@@ -1,28 +1,77 @@
-use crate::{at, simulate_think};
-use crate::cards::deck::Deck;
+use crate::at;
+use crate::cards::card::{Card, CardSymbol};
+use crate::cards::shoe::Shoe;
+use crate::error::BlackjackError;
 use crate::gameplay::actor::{Actor, ActorRole};
-use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::blackjack::{blackjack_card_value, UserAction};
+use crate::gameplay::dealer::DealerPolicy;
+use crate::gameplay::decision::DecisionProvider;
+use crate::gameplay::event::{GameEvent, Observers};
 use crate::gameplay::hand::{Hand, HandState};
+use crate::gameplay::rules::{HoleCardRule, Surrender, TableRules};
+use crate::gameplay::wager::Wager;
+
+/// A player dealt into a round.
+#[derive(Debug, Clone)]
+pub struct Seat {
+    pub player_idx: usize,
+    pub name: String,
+    pub bet: i32,
+    /// Coins the player can put on the table, bet included.
+    pub bankroll: i32,
+}
 
 pub struct Round {
-    pub deck: Deck,
+    pub rules: TableRules,
     pub actors: Vec<Actor>,
-    pub actor_bets: Vec<i32>,
+    /// Player behind each actor, the dealer excluded.
+    pub seats: Vec<Seat>,
 }
 
 impl Round {
-    pub fn play(&mut self) {
-        self.deal_initial_cards();
-        self.setup_dealer();
+    pub fn play(
+        &mut self,
+        shoe: &mut Shoe,
+        decisions: &mut dyn DecisionProvider,
+        observers: &mut Observers,
+    ) -> Result<(), BlackjackError> {
+        shuffle_if_needed(shoe, observers);
+
+        observers.emit(GameEvent::RoundStarted {
+            actors: self.actors.iter().map(|actor| actor.name.clone()).collect(),
+            bets: self.seats.iter().map(|seat| seat.bet).collect(),
+        });
+        self.setup_dealer()?;
+        self.deal_initial_cards(shoe, observers)?;
+        self.update_state()?;
+        observers.emit(GameEvent::InitialCardsDealt);
+
+        if self.dealer_hand()?.card_at(0)?.value == CardSymbol::Ace {
+            self.offer_insurance(decisions, observers)?;
+        }
 
-        self.update();
+        if self.dealer_may_have_blackjack()? {
+            if self.rules.surrender == Surrender::Early {
+                self.offer_early_surrender(decisions, observers)?;
+            }
+
+            if self.rules.hole_card == HoleCardRule::Peek {
+                let blackjack = self.dealer_hand()?.is_natural();
+                observers.emit(GameEvent::DealerPeeked { blackjack });
+                if blackjack {
+                    self.reveal_hole_card(observers)?;
+                    self.finish_player_hands();
+                    return Ok(());
+                }
+            }
+        }
 
         let mut actor_cursor = 0;
         let mut hand_cursor = 0;
 
         loop {
-            let actor_name = at!(self.actors, actor_cursor).name.clone();
-            let actor = at!(mut self.actors, actor_cursor);
+            let dealer_up_card = self.dealer_hand()?.card_at(0)?.clone();
+            let actor = at!(self.actors, actor_cursor)?;
             match actor.role {
                 ActorRole::Dealer => {
                     break;
@@ -35,149 +84,406 @@ impl Round {
                         continue;
                     }
 
-                    let hand = actor.hand_at_mut(hand_cursor);
-                    match hand.state {
-                        HandState::Finished => {
-                            hand_cursor += 1;
-                        }
-                        HandState::Bust => {
-                            println!("Hand --> BUST \n");
-                            hand_cursor += 1;
-                        }
-                        HandState::Blackjack => {
-                            println!("Hand --> BlackJack! \n");
-                            hand_cursor += 1;
-                        }
+                    let hand = actor.hand_at(hand_cursor)?;
+                    let event = match hand.state {
+                        HandState::Finished | HandState::Surrendered => None,
+                        HandState::Bust => Some(GameEvent::HandBusted {
+                            actor_idx: actor_cursor,
+                            hand_idx: hand_cursor,
+                        }),
+                        HandState::Natural => Some(GameEvent::HandNatural {
+                            actor_idx: actor_cursor,
+                            hand_idx: hand_cursor,
+                        }),
+                        HandState::TwentyOne => Some(GameEvent::HandTwentyOne {
+                            actor_idx: actor_cursor,
+                            hand_idx: hand_cursor,
+                        }),
                         HandState::Undefined => {
-                            match hand.prompt_user_action(&actor_name, hand_cursor) {
-                                UserAction::Hit => {
-                                    println!("Hand --> HIT \n");
-                                    hand.deal_card(self.deck.draw_card());
-                                    self.update();
-                                }
-                                UserAction::Split => {
-                                    println!("Hand --> SPLIT \n");
-                                    let new_hand =
-                                        hand.split(self.deck.draw_card(), self.deck.draw_card());
-                                    actor.hands.insert(hand_cursor + 1, new_hand);
-                                    self.update();
-                                }
-                                UserAction::Stay => {
-                                    println!("Hand --> STAY \n");
-                                    hand.state = HandState::Finished;
-                                    hand_cursor += 1;
-                                }
-                            }
+                            let actions = self.playable_actions(actor_cursor, hand_cursor)?;
+                            // one card only on split aces
+                            let action = if actions == [UserAction::Stay] {
+                                UserAction::Stay
+                            } else {
+                                decisions.action(
+                                    at!(self.seats, actor_cursor)?.player_idx,
+                                    actor,
+                                    hand_cursor,
+                                    &dealer_up_card,
+                                    &actions,
+                                )?
+                            };
+                            self.check_action(actor_cursor, hand_cursor, action, &actions)?;
+                            self.take_action(shoe, observers, actor_cursor, hand_cursor, action)?;
+                            continue;
                         }
+                    };
+
+                    if let Some(event) = event {
+                        observers.emit(event);
                     }
+                    hand_cursor += 1;
                 }
             }
         }
 
-        simulate_think!(2);
-
-        self.dealer_hand_mut().card_at_mut(1).reveal();
-
-        self.update();
-
-        simulate_think!(2);
+        let dealer_idx = self.actors.len() - 1;
+        match self.rules.hole_card {
+            HoleCardRule::Peek => self.reveal_hole_card(observers)?,
+            HoleCardRule::NoHoleCard(_) => self.deal_card(shoe, observers, dealer_idx, 0)?,
+        }
 
         loop {
-            let dealer_hand = self.dealer_hand();
+            let dealer_hand = self.dealer_hand()?;
             match dealer_hand.state {
-                HandState::Finished => unreachable!(),
+                HandState::Finished | HandState::Surrendered => break,
                 HandState::Bust => {
-                    println!("Dealer --> BUST \n");
+                    observers.emit(GameEvent::HandBusted {
+                        actor_idx: dealer_idx,
+                        hand_idx: 0,
+                    });
                     break;
                 }
-                HandState::Blackjack => {
-                    println!("Dealer --> BLACKJACK! \n");
+                HandState::Natural => {
+                    observers.emit(GameEvent::HandNatural {
+                        actor_idx: dealer_idx,
+                        hand_idx: 0,
+                    });
                     break;
                 }
-                HandState::Undefined => match self.dealer_hand().sum {
-                    1..17 => {
-                        println!("Dealer --> HIT \n");
-                        let card = self.deck.draw_card();
-                        self.dealer_hand_mut().deal_card(card);
-                        simulate_think!(2);
-                        self.update();
-                    }
-                    17..21 => {
-                        println!("Dealer --> STAY \n");
-                        self.dealer_hand_mut().state = HandState::Finished;
+                HandState::TwentyOne => {
+                    observers.emit(GameEvent::HandTwentyOne {
+                        actor_idx: dealer_idx,
+                        hand_idx: 0,
+                    });
+                    break;
+                }
+                HandState::Undefined => {
+                    let action = DealerPolicy::from_rules(&self.rules).action(&dealer_hand.value);
+                    observers.emit(GameEvent::ActionTaken {
+                        actor_idx: dealer_idx,
+                        hand_idx: 0,
+                        action,
+                    });
+                    if action == UserAction::Stay {
+                        self.dealer_hand_mut()?.state = HandState::Finished;
                         break;
                     }
-                    _ => unreachable!(),
-                },
+                    self.deal_card(shoe, observers, dealer_idx, 0)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn dealer_hand(&self) -> Result<&Hand, BlackjackError> {
+        self.dealer()?.hand_at(0)
+    }
+    fn dealer_hand_mut(&mut self) -> Result<&mut Hand, BlackjackError> {
+        self.dealer_mut()?.hand_at_mut(0)
+    }
+
+    fn dealer(&self) -> Result<&Actor, BlackjackError> {
+        self.actors.last().ok_or(BlackjackError::OutOfRange(0))
+    }
+
+    fn dealer_mut(&mut self) -> Result<&mut Actor, BlackjackError> {
+        self.actors.last_mut().ok_or(BlackjackError::OutOfRange(0))
+    }
+
+    fn hand_mut(&mut self, actor_idx: usize, hand_idx: usize) -> Result<&mut Hand, BlackjackError> {
+        at!(mut self.actors, actor_idx)?.hand_at_mut(hand_idx)
+    }
+
+    /// Coins the actor has on the table, insurance included.
+    fn committed(&self, actor_idx: usize) -> Result<i32, BlackjackError> {
+        let actor = at!(self.actors, actor_idx)?;
+        Ok(actor
+            .hands
+            .iter()
+            .map(|hand| hand.wager.at_risk() + hand.wager.insurance)
+            .sum())
+    }
+
+    /// Bankroll left after everything the actor has on the table.
+    fn available_funds(&self, actor_idx: usize) -> Result<i32, BlackjackError> {
+        Ok(at!(self.seats, actor_idx)?.bankroll - self.committed(actor_idx)?)
+    }
+
+    /// Actions allowed by the rules that the player can also pay for.
+    fn playable_actions(
+        &self,
+        actor_idx: usize,
+        hand_idx: usize,
+    ) -> Result<Vec<UserAction>, BlackjackError> {
+        let actor = at!(self.actors, actor_idx)?;
+        let hand = actor.hand_at(hand_idx)?;
+        let mut actions = hand.available_actions(&self.rules, actor.hands.len());
+        if self.available_funds(actor_idx)? < hand.wager.main {
+            actions.retain(|action| !matches!(action, UserAction::Double | UserAction::Split));
+        }
+        Ok(actions)
+    }
+
+    fn check_action(
+        &self,
+        actor_idx: usize,
+        hand_idx: usize,
+        action: UserAction,
+        actions: &[UserAction],
+    ) -> Result<(), BlackjackError> {
+        if actions.contains(&action) {
+            return Ok(());
+        }
+
+        let actor = at!(self.actors, actor_idx)?;
+        let hand = actor.hand_at(hand_idx)?;
+        if hand
+            .available_actions(&self.rules, actor.hands.len())
+            .contains(&action)
+        {
+            return Err(BlackjackError::InsufficientFunds {
+                bankroll: self.available_funds(actor_idx)?,
+                amount: hand.wager.main,
+            });
+        }
+        Err(BlackjackError::InvalidAction(action))
+    }
+
+    fn take_action(
+        &mut self,
+        shoe: &mut Shoe,
+        observers: &mut Observers,
+        actor_idx: usize,
+        hand_idx: usize,
+        action: UserAction,
+    ) -> Result<(), BlackjackError> {
+        observers.emit(GameEvent::ActionTaken {
+            actor_idx,
+            hand_idx,
+            action,
+        });
+        match action {
+            UserAction::Hit => self.deal_card(shoe, observers, actor_idx, hand_idx)?,
+            UserAction::Double => {
+                let hand = self.hand_mut(actor_idx, hand_idx)?;
+                hand.wager.double = hand.wager.main;
+                self.deal_card(shoe, observers, actor_idx, hand_idx)?;
+                let hand = self.hand_mut(actor_idx, hand_idx)?;
+                if matches!(hand.state, HandState::Undefined) {
+                    hand.state = HandState::Finished;
+                }
+            }
+            UserAction::Split => {
+                observers.emit(GameEvent::HandSplit {
+                    actor_idx,
+                    hand_idx,
+                });
+                let card_0_1 = draw_card(shoe, observers)?;
+                let card_1_1 = draw_card(shoe, observers)?;
+                observers.emit(GameEvent::CardDealt {
+                    actor_idx,
+                    hand_idx,
+                    card: card_0_1.clone(),
+                });
+                observers.emit(GameEvent::CardDealt {
+                    actor_idx,
+                    hand_idx: hand_idx + 1,
+                    card: card_1_1.clone(),
+                });
+                let actor = at!(mut self.actors, actor_idx)?;
+                let mut new_hand = actor.hand_at_mut(hand_idx)?.split(card_0_1, card_1_1)?;
+                new_hand.update_state()?;
+                actor.hand_at_mut(hand_idx)?.update_state()?;
+                actor.hands.insert(hand_idx + 1, new_hand);
+            }
+            UserAction::Surrender => {
+                self.hand_mut(actor_idx, hand_idx)?.state = HandState::Surrendered;
+            }
+            UserAction::Stay => {
+                self.hand_mut(actor_idx, hand_idx)?.state = HandState::Finished;
             }
         }
+        Ok(())
     }
 
-    pub(crate) fn dealer_hand(&self) -> &Hand {
-        self.dealer().hand_at(0)
+    fn deal_card(
+        &mut self,
+        shoe: &mut Shoe,
+        observers: &mut Observers,
+        actor_idx: usize,
+        hand_idx: usize,
+    ) -> Result<(), BlackjackError> {
+        let card = draw_card(shoe, observers)?;
+        observers.emit(GameEvent::CardDealt {
+            actor_idx,
+            hand_idx,
+            card: card.clone(),
+        });
+        let hand = self.hand_mut(actor_idx, hand_idx)?;
+        hand.deal_card(card);
+        hand.update_state()?;
+        Ok(())
     }
-    fn dealer_hand_mut(&mut self) -> &mut Hand {
-        self.dealer_mut().hand_at_mut(0)
+
+    fn reveal_hole_card(&mut self, observers: &mut Observers) -> Result<(), BlackjackError> {
+        let hole_card = self.dealer_hand_mut()?.card_at_mut(1)?;
+        hole_card.reveal();
+        observers.emit(GameEvent::HoleCardRevealed {
+            card: hole_card.clone(),
+        });
+        Ok(())
     }
 
-    fn dealer(&self) -> &Actor {
-        self.actors.last().unwrap()
+    fn setup_dealer(&mut self) -> Result<(), BlackjackError> {
+        self.dealer_mut()?.role = ActorRole::Dealer;
+        Ok(())
     }
 
-    fn dealer_mut(&mut self) -> &mut Actor {
-        self.actors.last_mut().unwrap()
+    /// Dealer shows an ace or a ten.
+    fn dealer_may_have_blackjack(&self) -> Result<bool, BlackjackError> {
+        let up_card = &self.dealer_hand()?.card_at(0)?.value;
+        Ok(blackjack_card_value(up_card) >= 10)
     }
 
-    fn update(&mut self) {
-        self.update_state();
-        self.print_current_game_state();
+    fn offer_early_surrender(
+        &mut self,
+        decisions: &mut dyn DecisionProvider,
+        observers: &mut Observers,
+    ) -> Result<(), BlackjackError> {
+        let dealer_up_card = self.dealer_hand()?.card_at(0)?.clone();
+        for actor_idx in 0..self.actors.len() - 1 {
+            let seat = at!(self.seats, actor_idx)?.player_idx;
+            let actor = at!(mut self.actors, actor_idx)?;
+            let hand = actor.hand_at(0)?;
+            if hand.is_natural() || hand.even_money {
+                continue;
+            }
+
+            if decisions.early_surrender(seat, actor, &dealer_up_card)? {
+                observers.emit(GameEvent::ActionTaken {
+                    actor_idx,
+                    hand_idx: 0,
+                    action: UserAction::Surrender,
+                });
+                actor.hand_at_mut(0)?.state = HandState::Surrendered;
+            }
+        }
+        Ok(())
     }
 
-    fn print_current_game_state(&self) {
-        for actor in self.actors.iter() {
-            for (idx, hand) in actor.hands.iter().enumerate() {
-                println!("{}:{} {{ {} }}", actor.name, idx + 1, hand.describe());
+    /// Dealer has a natural, the players' hands stand as dealt.
+    fn finish_player_hands(&mut self) {
+        let player_count = self.actors.len() - 1;
+        for actor in self.actors.iter_mut().take(player_count) {
+            for hand in actor.hands.iter_mut() {
+                if matches!(hand.state, HandState::Undefined) {
+                    hand.state = HandState::Finished;
+                }
             }
         }
     }
 
-    fn setup_dealer(&mut self) {
-        let dealer = self.dealer_mut();
-        dealer.role = ActorRole::Dealer;
-        dealer.hand_at_mut(0).card_at_mut(1).hide();
+    /// Dealer shows an ace: insurance for everyone, even money for players holding a natural.
+    fn offer_insurance(
+        &mut self,
+        decisions: &mut dyn DecisionProvider,
+        observers: &mut Observers,
+    ) -> Result<(), BlackjackError> {
+        for actor_idx in 0..self.actors.len() - 1 {
+            let seat = at!(self.seats, actor_idx)?.player_idx;
+            let available_funds = self.available_funds(actor_idx)?;
+            let actor = at!(mut self.actors, actor_idx)?;
+            if actor.hand_at(0)?.is_natural() {
+                if decisions.even_money(seat, actor)? {
+                    observers.emit(GameEvent::EvenMoneyTaken { actor_idx });
+                    actor.hand_at_mut(0)?.even_money = true;
+                }
+                continue;
+            }
+
+            let max_insurance = actor.hand_at(0)?.wager.max_insurance().min(available_funds);
+            let insurance = decisions
+                .insurance(seat, actor, max_insurance)?
+                .max(0)
+                .min(max_insurance);
+            if insurance > 0 {
+                observers.emit(GameEvent::InsuranceTaken {
+                    actor_idx,
+                    amount: insurance,
+                });
+            }
+            actor.hand_at_mut(0)?.wager.insurance = insurance;
+        }
+        Ok(())
     }
 
-    fn deal_initial_cards(&mut self) {
-        for i in 0..self.actors.len() {
-            let hand = at!(mut self.actors, i).hand_at_mut(0);
-            hand.deal_card(self.deck.draw_card());
-            hand.deal_card(self.deck.draw_card());
+    /// The dealer's second card is dealt face down.
+    fn deal_initial_cards(
+        &mut self,
+        shoe: &mut Shoe,
+        observers: &mut Observers,
+    ) -> Result<(), BlackjackError> {
+        let dealer_idx = self.actors.len() - 1;
+        for actor_idx in 0..self.actors.len() {
+            self.deal_card(shoe, observers, actor_idx, 0)?;
+            if actor_idx != dealer_idx {
+                self.deal_card(shoe, observers, actor_idx, 0)?;
+            } else if self.rules.hole_card == HoleCardRule::Peek {
+                let mut hole_card = draw_card(shoe, observers)?;
+                hole_card.hide();
+                observers.emit(GameEvent::HoleCardDealt { actor_idx });
+                self.dealer_hand_mut()?.deal_card(hole_card);
+            }
         }
+        Ok(())
     }
 
-    fn update_state(&mut self) {
+    fn update_state(&mut self) -> Result<(), BlackjackError> {
         for actor in self.actors.iter_mut() {
             for hand in actor.hands.iter_mut() {
-                hand.update_state();
+                hand.update_state()?;
             }
         }
+        Ok(())
+    }
+}
+
+/// Next card off the shoe. A shoe that runs dry mid-round gets the discards shuffled back in,
+/// the cards on the table stay out. `OutOfCards` once there are no discards either.
+fn draw_card(shoe: &mut Shoe, observers: &mut Observers) -> Result<Card, BlackjackError> {
+    if shoe.remaining_cards() == 0 && shoe.shuffle_discards() {
+        observers.emit(GameEvent::DiscardsShuffled {
+            cards: shoe.remaining_cards(),
+        });
+    }
+    shoe.draw_card()
+}
+
+/// Shuffles once the cut card is out.
+pub fn shuffle_if_needed(shoe: &mut Shoe, observers: &mut Observers) {
+    if shoe.needs_shuffle() {
+        shoe.shuffle();
+        observers.emit(GameEvent::ShoeShuffled {
+            number_of_decks: shoe.number_of_decks,
+        });
     }
 }
 
-pub fn blackjack_round(number_of_user_players: usize, bet: i32) -> Round {
-    let deck = Deck::new_shuffled();
-    let mut actors = Vec::with_capacity(number_of_user_players + 1);
-    let actor_bets = vec![bet; number_of_user_players]; // dealer is not betting
+pub fn blackjack_round(seats: &[Seat], rules: TableRules) -> Round {
+    let mut actors = Vec::with_capacity(seats.len() + 1);
 
-    for actor_idx in 0..number_of_user_players {
-        actors.push(Actor::new(format!("User_{}", actor_idx + 1), Hand::new()))
+    for seat in seats {
+        let mut hand = Hand::new();
+        hand.wager = Wager::new(seat.bet);
+        actors.push(Actor::new(seat.name.clone(), hand))
     }
 
-    actors.push(Actor::new("Dealer".to_string(), Hand::new()));
+    actors.push(Actor::new("Dealer".to_string(), Hand::new())); // dealer is not betting
 
     Round {
+        rules,
         actors,
-        actor_bets,
-        deck,
+        seats: seats.to_vec(),
     }
 }
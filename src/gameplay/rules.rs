@@ -0,0 +1,186 @@
+use crate::error::BlackjackError;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BlackjackPayout {
+    ThreeToTwo,
+    SixToFive,
+    EvenMoney,
+}
+
+impl BlackjackPayout {
+    pub fn pay(self, bet: i32) -> i32 {
+        match self {
+            BlackjackPayout::ThreeToTwo => (3 * bet) / 2,
+            BlackjackPayout::SixToFive => (6 * bet) / 5,
+            BlackjackPayout::EvenMoney => bet,
+        }
+    }
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            BlackjackPayout::ThreeToTwo => "3:2",
+            BlackjackPayout::SixToFive => "6:5",
+            BlackjackPayout::EvenMoney => "1:1",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DoubleRule {
+    AnyTwoCards,
+    NineToEleven,
+    TenToEleven,
+    NotAllowed,
+}
+
+impl DoubleRule {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            DoubleRule::AnyTwoCards => "double on any two cards",
+            DoubleRule::NineToEleven => "double on 9-11 only",
+            DoubleRule::TenToEleven => "double on 10-11 only",
+            DoubleRule::NotAllowed => "no doubling",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Surrender {
+    NotAllowed,
+    Late,
+    Early,
+}
+
+impl Surrender {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Surrender::NotAllowed => "no surrender",
+            Surrender::Late => "late surrender",
+            Surrender::Early => "early surrender",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NoHoleCardLoss {
+    /// OBO, a dealer blackjack only takes the original bet, doubles and splits are returned.
+    OriginalBetsOnly,
+    /// ENHC, a dealer blackjack takes doubles and splits as well.
+    AllBets,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HoleCardRule {
+    /// American style, the dealer peeks for blackjack under an ace or a ten.
+    Peek,
+    /// European style, the dealer's second card is drawn after the players act.
+    NoHoleCard(NoHoleCardLoss),
+}
+
+impl HoleCardRule {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            HoleCardRule::Peek => "dealer peeks for blackjack",
+            HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly) => {
+                "no hole card, original bets only"
+            }
+            HoleCardRule::NoHoleCard(NoHoleCardLoss::AllBets) => "no hole card, all bets lost",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TableRules {
+    pub table_minimum: i32,
+    pub table_maximum: i32,
+    pub number_of_decks: usize,
+    pub penetration: f32,
+    pub dealer_hits_soft_17: bool,
+    pub blackjack_payout: BlackjackPayout,
+    pub double: DoubleRule,
+    pub double_after_split: bool,
+    /// Maximum number of hands a player may split into, 4 means "split to 4 hands".
+    pub max_split_hands: usize,
+    pub resplit_aces: bool,
+    pub hit_split_aces: bool,
+    /// Any two ten-valued cards can be split, e.g. K+Q.
+    pub split_unlike_tens: bool,
+    pub surrender: Surrender,
+    pub hole_card: HoleCardRule,
+}
+
+impl Default for TableRules {
+    fn default() -> Self {
+        Self {
+            table_minimum: 10,
+            table_maximum: 500,
+            number_of_decks: 6,
+            penetration: 0.75,
+            dealer_hits_soft_17: false,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+            double: DoubleRule::AnyTwoCards,
+            double_after_split: true,
+            max_split_hands: 4,
+            resplit_aces: false,
+            hit_split_aces: false,
+            split_unlike_tens: false,
+            surrender: Surrender::NotAllowed,
+            hole_card: HoleCardRule::Peek,
+        }
+    }
+}
+
+impl TableRules {
+    /// `InvalidInput` for table limits no bet fits in.
+    pub fn validate(&self) -> Result<(), BlackjackError> {
+        if self.table_minimum > self.table_maximum {
+            return Err(BlackjackError::InvalidInput(format!(
+                "bets {}-{}",
+                self.table_minimum, self.table_maximum
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn describe(&self) -> String {
+        let mut rules = vec![
+            format!("bets {}-{}", self.table_minimum, self.table_maximum),
+            format!("{} deck(s)", self.number_of_decks),
+            format!("{:.0}% penetration", self.penetration * 100.0),
+            if self.dealer_hits_soft_17 {
+                "dealer hits soft 17".to_string()
+            } else {
+                "dealer stands on soft 17".to_string()
+            },
+            format!("blackjack pays {}", self.blackjack_payout.to_str()),
+            self.double.to_str().to_string(),
+        ];
+
+        if self.double != DoubleRule::NotAllowed {
+            rules.push(if self.double_after_split {
+                "double after split".to_string()
+            } else {
+                "no double after split".to_string()
+            });
+        }
+
+        rules.push(format!("split up to {} hands", self.max_split_hands));
+        rules.push(if self.resplit_aces {
+            "resplit aces".to_string()
+        } else {
+            "no resplitting aces".to_string()
+        });
+        rules.push(if self.hit_split_aces {
+            "hit split aces".to_string()
+        } else {
+            "one card on split aces".to_string()
+        });
+        if self.split_unlike_tens {
+            rules.push("split any two tens".to_string());
+        }
+        rules.push(self.surrender.to_str().to_string());
+        rules.push(self.hole_card.to_str().to_string());
+
+        rules.join(", ")
+    }
+}
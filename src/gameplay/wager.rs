@@ -0,0 +1,31 @@
+/// Money riding on a single hand.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Wager {
+    pub main: i32,
+    /// Added on top of `main` when doubling down.
+    pub double: i32,
+    /// Side bet against a dealer natural, pays 2:1.
+    pub insurance: i32,
+}
+
+impl Wager {
+    pub fn new(main: i32) -> Self {
+        Wager {
+            main,
+            ..Wager::default()
+        }
+    }
+
+    pub fn is_doubled(&self) -> bool {
+        self.double > 0
+    }
+
+    pub fn max_insurance(&self) -> i32 {
+        self.main / 2
+    }
+
+    /// Main bet and double, everything won or lost with the hand itself.
+    pub fn at_risk(&self) -> i32 {
+        self.main + self.double
+    }
+}
@@ -0,0 +1,66 @@
+//! Keyboard prompts and pauses for the terminal game.
+
+/// Waits for one of the given keys, `Err` once stdin is closed.
+macro_rules! take_stdin_key {
+    ($msg:expr $(,$char:literal)*) => {{
+        println!("{}", $msg);
+        use std::io::{stdin, stdout, Write};
+        use termion::event::Key;
+        use termion::input::TermRead;
+        use termion::raw::IntoRawMode;
+
+        (|| -> Result<char, rust_blackjack::error::BlackjackError> {
+            let mut stdout = stdout().into_raw_mode()?;
+            for c in stdin().keys() {
+                let x = match c? {
+                    $(Key::Char($char) => $char,)*
+                    _ => '\0'
+                };
+
+                if x != '\0' {
+                    stdout.flush()?;
+                    return Ok(x);
+                }
+            }
+            Err(rust_blackjack::error::BlackjackError::InputClosed)
+        })()
+    }}
+}
+
+/// Reads a trimmed line, `Err` once stdin is closed.
+macro_rules! take_stdin_string {
+    ($msg:expr, $take:literal) => {{
+        println!("{}", $msg);
+        use std::string::String;
+        (|| -> Result<String, rust_blackjack::error::BlackjackError> {
+            let mut s = String::new();
+            if std::io::stdin().read_line(&mut s)? == 0 {
+                return Err(rust_blackjack::error::BlackjackError::InputClosed);
+            }
+            Ok(s.as_str().trim().to_string())
+        })()
+    }};
+}
+
+#[allow(unused_macros)]
+macro_rules! take_stdin {
+    ($msg:expr, $t:ty, $take:literal) => {
+        take_stdin_string!($msg, $take).and_then(|s| {
+            s.parse::<$t>()
+                .map_err(|_| rust_blackjack::error::BlackjackError::InvalidInput(s))
+        })
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! take_stdin_str {
+    ($msg:expr, $take:literal) => {
+        take_stdin_string!($msg, $take).as_str()
+    };
+}
+
+macro_rules! simulate_think {
+    ($seconds:literal) => {
+        std::thread::sleep(std::time::Duration::from_secs($seconds));
+    };
+}
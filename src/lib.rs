@@ -0,0 +1,11 @@
+//! Blackjack engine: cards, shoe, table rules and a game loop driven by a `DecisionProvider`,
+//! reporting everything that happens to `GameObserver`s.
+
+pub mod cards;
+pub mod error;
+pub mod gameplay;
+mod macros;
+pub mod simulation;
+pub mod strategy;
+
+pub(crate) use macros::at;
@@ -1,19 +1,93 @@
-#![feature(exclusive_range_pattern)]
-
+use std::env;
 use std::process::exit;
-use std::vec;
 
-use gameplay::round::blackjack_round;
+use rand::{thread_rng, Rng};
+
+use rust_blackjack::cards::deck::Deck;
+use rust_blackjack::cards::shoe::Shoe;
+use rust_blackjack::error::BlackjackError;
+use rust_blackjack::gameplay::event::Observers;
+use rust_blackjack::gameplay::game::Game;
+use rust_blackjack::gameplay::player::Player;
+use rust_blackjack::gameplay::rules::{
+    BlackjackPayout, DoubleRule, HoleCardRule, NoHoleCardLoss, Surrender, TableRules,
+};
+use rust_blackjack::strategy::basic::BasicStrategy;
+use rust_blackjack::strategy::chart::{Chart, ChartStrategy};
+use rust_blackjack::strategy::counting::{CountingStrategy, CountingSystem};
+use rust_blackjack::strategy::{Strategy, TableDecisions};
+
+use crate::terminal::{
+    print_player_scores, print_session_summary, TerminalInput, TerminalRenderer,
+};
+
+#[macro_use]
+mod input;
+mod terminal;
+
+fn exit_with_usage() -> ! {
+    println!("Usage: rust-blackjack [--seed <u64>] [--deck \"AS KD 8H 8C ...\"]");
+    exit(-1);
+}
+
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    match args.next() {
+        Some(value) => Some(value),
+        None => exit_with_usage(),
+    }
+}
+
+fn seed_from_args() -> Option<u64> {
+    arg_value("--seed").map(|value| value.parse().unwrap_or_else(|_| exit_with_usage()))
+}
 
-use crate::gameplay::game::Game;
+fn stacked_deck_from_args() -> Option<Deck> {
+    arg_value("--deck").map(|value| {
+        value.parse().unwrap_or_else(|err| {
+            println!("{}", err);
+            exit_with_usage()
+        })
+    })
+}
 
-mod cards;
-mod gameplay;
-mod macros;
+/// Asks for a chart file until one loads.
+fn chart_from_stdin() -> Result<Chart, BlackjackError> {
+    loop {
+        let path = take_stdin_string!("Chart file? (CSV, see analyze --export)", 1)?;
+        match Chart::load(&path) {
+            Ok(chart) => return Ok(chart),
+            Err(err) => println!("{}", err),
+        }
+    }
+}
 
 fn main() {
-    let minimum_bet = 10;
-    let number_of_players = match take_stdin_key!("Number of players? [1/2/3]", '1', '2', '3') {
+    if let Err(err) = run() {
+        println!("{}", err);
+        exit(-1);
+    }
+}
+
+/// Rounds until the players are done, an `Err` ends the session early.
+fn play_session(game: &mut Game) -> Result<(), BlackjackError> {
+    while game.play_round()? {
+        simulate_think!(2);
+        print_player_scores(&game.players);
+        if !game.decisions.another_round()? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), BlackjackError> {
+    let mut rules = TableRules::default();
+    let seed = seed_from_args().unwrap_or_else(|| thread_rng().gen());
+    println!("Seed: {} (replay with --seed {})", seed, seed);
+
+    let number_of_players = match take_stdin_key!("Number of players? [1/2/3]", '1', '2', '3')? {
         '1' => 1,
         '2' => 2,
         '3' => 3,
@@ -26,45 +100,158 @@ fn main() {
     } else {
         println!("{}", number_of_players);
     }
-    // let number_of_players = 3;
-    let player_scores = vec![minimum_bet * 10; number_of_players];
 
-    let player_names = vec![""; number_of_players]
-        .iter()
-        .enumerate()
-        .map(|(idx, _)| format!("User_{}", idx + 1))
-        .collect();
+    let number_of_bots = match take_stdin_key!("Computer players? [0/1/2/3]", '0', '1', '2', '3')? {
+        '0' => 0,
+        '1' => 1,
+        '2' => 2,
+        '3' => 3,
+        _ => unreachable!(),
+    };
+    println!("{}", number_of_bots);
 
+    let (table_minimum, table_maximum) =
+        match take_stdin_key!("Table limits? [1=5-100/2=10-500/3=25-1000]", '1', '2', '3')? {
+            '1' => (5, 100),
+            '2' => (10, 500),
+            '3' => (25, 1000),
+            _ => unreachable!(),
+        };
+    rules.table_minimum = table_minimum;
+    rules.table_maximum = table_maximum;
 
-    let mut game = Game {
-        player_scores,
-        player_names,
+    rules.number_of_decks = match take_stdin_key!("Number of decks? [1/2/6/8]", '1', '2', '6', '8')?
+    {
+        '1' => 1,
+        '2' => 2,
+        '6' => 6,
+        '8' => 8,
+        _ => unreachable!(),
     };
+    println!("{}", rules.number_of_decks);
 
-    loop {
-        let mut round = blackjack_round(number_of_players, minimum_bet);
-        round.play();
+    rules.dealer_hits_soft_17 =
+        take_stdin_key!("Dealer on soft 17? [s=stand/h=hit]", 's', 'h')? == 'h';
 
-        simulate_think!(1);
-        game.judge_round(&round);
+    rules.blackjack_payout =
+        match take_stdin_key!("Blackjack pays? [3=3:2/6=6:5/1=1:1]", '3', '6', '1')? {
+            '3' => BlackjackPayout::ThreeToTwo,
+            '6' => BlackjackPayout::SixToFive,
+            '1' => BlackjackPayout::EvenMoney,
+            _ => unreachable!(),
+        };
 
-        simulate_think!(2);
-        game.print_player_scores();
-
-        loop {
-            println!();
-            match take_stdin_key!("Another round? [y/n]:", 'y', 'n') {
-                'y' => {
-                    break;
-                }
-                'n' => {
-                    println!("Thanks for playing, bye :)");
-                    exit(0);
-                }
-                _ => {
-                    unreachable!()
-                }
+    rules.double = match take_stdin_key!(
+        "Double down on? [a=any two/9=9-11/t=10-11/n=never]",
+        'a',
+        '9',
+        't',
+        'n'
+    )? {
+        'a' => DoubleRule::AnyTwoCards,
+        '9' => DoubleRule::NineToEleven,
+        't' => DoubleRule::TenToEleven,
+        'n' => DoubleRule::NotAllowed,
+        _ => unreachable!(),
+    };
+
+    rules.max_split_hands = match take_stdin_key!("Split up to? [2/3/4] hands", '2', '3', '4')? {
+        '2' => 2,
+        '3' => 3,
+        '4' => 4,
+        _ => unreachable!(),
+    };
+
+    rules.surrender = match take_stdin_key!("Surrender? [n=none/l=late/e=early]", 'n', 'l', 'e')? {
+        'n' => Surrender::NotAllowed,
+        'l' => Surrender::Late,
+        'e' => Surrender::Early,
+        _ => unreachable!(),
+    };
+
+    rules.hole_card = match take_stdin_key!(
+        "Dealer hole card? [p=peek/o=no hole card, OBO/e=no hole card, ENHC]",
+        'p',
+        'o',
+        'e'
+    )? {
+        'p' => HoleCardRule::Peek,
+        'o' => HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly),
+        'e' => HoleCardRule::NoHoleCard(NoHoleCardLoss::AllBets),
+        _ => unreachable!(),
+    };
+
+    println!("Table rules: {}", rules.describe());
+
+    // let number_of_players = 3;
+    let buy_in = rules.table_minimum * 10;
+    let players = (0..number_of_players)
+        .map(|idx| format!("User_{}", idx + 1))
+        .chain((0..number_of_bots).map(|idx| format!("Bot_{}", idx + 1)))
+        .map(|name| Player::new(name, buy_in, rules.table_minimum))
+        .collect();
+
+    let mut strategies: Vec<Option<Box<dyn Strategy>>> = Vec::new();
+    let mut counters = Vec::new();
+    for _ in 0..number_of_players {
+        strategies.push(None);
+    }
+    for idx in 0..number_of_bots {
+        let prompt = format!(
+            "Bot_{} plays? [b=basic strategy/h=Hi-Lo/k=KO/o=Hi-Opt II/m=Omega II/z=Zen/c=chart file]",
+            idx + 1
+        );
+        let key = take_stdin_key!(prompt, 'b', 'h', 'k', 'o', 'm', 'z', 'c')?;
+        let system = match key {
+            'h' => Some(CountingSystem::HI_LO),
+            'k' => Some(CountingSystem::KO),
+            'o' => Some(CountingSystem::HI_OPT_II),
+            'm' => Some(CountingSystem::OMEGA_II),
+            'z' => Some(CountingSystem::ZEN),
+            _ => None,
+        };
+        match system {
+            Some(system) => {
+                let strategy = CountingStrategy::new(system, &rules);
+                counters.push(strategy.counter.clone());
+                strategies.push(Some(Box::new(strategy)));
+            }
+            None if key == 'c' => {
+                let chart = chart_from_stdin()?;
+                strategies.push(Some(Box::new(ChartStrategy::new(chart))));
             }
+            None => strategies.push(Some(Box::new(BasicStrategy::new(&rules)))),
         }
     }
+
+    let mut shoe = Shoe::with_seed(rules.number_of_decks, rules.penetration, seed)?;
+    if let Some(deck) = stacked_deck_from_args() {
+        shoe.stack(deck);
+    }
+
+    let mut game = Game {
+        players,
+        buy_in,
+        shoe,
+        rules,
+        decisions: Box::new(TableDecisions {
+            rules,
+            strategies,
+            humans: Box::new(TerminalInput),
+        }),
+        observers: Observers::default(),
+    };
+    game.observers
+        .register(Box::new(TerminalRenderer::default()));
+    for counter in counters {
+        game.observers.register(Box::new(counter));
+    }
+
+    if let Err(err) = play_session(&mut game) {
+        println!("Session ended: {}", err);
+    }
+
+    print_session_summary(&game.players);
+    println!("Thanks for playing, bye :)");
+    Ok(())
 }
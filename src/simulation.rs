@@ -0,0 +1,318 @@
+//! Headless play: bots only, no prompts and no pauses, results collected from the game events.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::cards::shoe::Shoe;
+use crate::error::BlackjackError;
+use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::event::{GameEvent, GameObserver, Observers};
+use crate::gameplay::game::{Game, HandResult};
+use crate::gameplay::player::Player;
+use crate::gameplay::rules::TableRules;
+use crate::strategy::basic::BasicStrategy;
+use crate::strategy::chart::{Chart, ChartStrategy};
+use crate::strategy::counting::{CountingStrategy, CountingSystem};
+use crate::strategy::{Strategy, TableDecisions};
+
+const ACTIONS: [UserAction; 5] = [
+    UserAction::Hit,
+    UserAction::Stay,
+    UserAction::Double,
+    UserAction::Split,
+    UserAction::Surrender,
+];
+
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub rules: TableRules,
+    pub seed: u64,
+    /// Initial hands to play, all seats together.
+    pub hands: u64,
+    /// Stop after this many shuffles instead, if set.
+    pub shoes: Option<u64>,
+    /// Bots at the table.
+    pub players: usize,
+    /// Counting system of the bots, flat bets if `None`.
+    pub system: Option<CountingSystem>,
+    /// Chart the flat betting bots play instead of basic strategy.
+    pub chart: Option<Chart>,
+}
+
+/// Collects results from the game events: money per initial hand, outcomes per settled hand
+/// and every decision taken by the players.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationStats {
+    pub rounds: u64,
+    /// Initial hands dealt, a split hand counts once.
+    pub hands: u64,
+    pub shoes: u64,
+    pub wagered: i64,
+    pub net: i64,
+    /// Sum of the results per initial hand, in initial bets.
+    pub sum_of_results: f64,
+    /// Sum of the squared results per initial hand, in initial bets.
+    pub sum_of_squares: f64,
+    /// Hands settled, split hands count on their own.
+    pub settled: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub blackjacks: u64,
+    pub surrenders: u64,
+    /// Decisions by `ACTIONS` order: hit, stay, double, split, surrender.
+    pub actions: [u64; 5],
+    /// `(player, initial bet, net)` of the round being played.
+    round: Vec<(String, i32, i64)>,
+    dealer_idx: usize,
+}
+
+impl SimulationStats {
+    /// Books the round in progress.
+    pub fn finish_round(&mut self) {
+        for (_, bet, net) in self.round.drain(..) {
+            if bet <= 0 {
+                continue;
+            }
+            self.hands += 1;
+            self.wagered += bet as i64;
+            self.net += net;
+            let result = net as f64 / bet as f64;
+            self.sum_of_results += result;
+            self.sum_of_squares += result * result;
+        }
+    }
+
+    pub fn merge(&mut self, other: &SimulationStats) {
+        self.rounds += other.rounds;
+        self.hands += other.hands;
+        self.shoes += other.shoes;
+        self.wagered += other.wagered;
+        self.net += other.net;
+        self.sum_of_results += other.sum_of_results;
+        self.sum_of_squares += other.sum_of_squares;
+        self.settled += other.settled;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.pushes += other.pushes;
+        self.blackjacks += other.blackjacks;
+        self.surrenders += other.surrenders;
+        for (count, other) in self.actions.iter_mut().zip(other.actions.iter()) {
+            *count += other;
+        }
+    }
+
+    /// Average coins won per initial hand.
+    pub fn ev_per_hand(&self) -> f64 {
+        ratio(self.net as f64, self.hands as f64)
+    }
+
+    /// Coins lost per coin of initial bet, in percent.
+    pub fn house_edge(&self) -> f64 {
+        -100.0 * ratio(self.net as f64, self.wagered as f64)
+    }
+
+    /// Standard deviation of the result per initial hand, in initial bets.
+    pub fn std_dev(&self) -> f64 {
+        let hands = self.hands as f64;
+        let mean = ratio(self.sum_of_results, hands);
+        (ratio(self.sum_of_squares, hands) - mean * mean)
+            .max(0.0)
+            .sqrt()
+    }
+
+    fn bot(&mut self, player: &str) -> Option<&mut (String, i32, i64)> {
+        self.round.iter_mut().find(|(name, _, _)| name == player)
+    }
+}
+
+fn ratio(a: f64, b: f64) -> f64 {
+    if b == 0.0 {
+        0.0
+    } else {
+        a / b
+    }
+}
+
+fn percent(count: u64, total: u64) -> f64 {
+    100.0 * ratio(count as f64, total as f64)
+}
+
+impl GameObserver for SimulationStats {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::ShoeShuffled { .. } => self.shoes += 1,
+            GameEvent::RoundStarted { actors, bets } => {
+                self.finish_round();
+                self.rounds += 1;
+                self.dealer_idx = actors.len().saturating_sub(1);
+                self.round = actors
+                    .iter()
+                    .zip(bets.iter())
+                    .map(|(name, bet)| (name.clone(), *bet, 0))
+                    .collect();
+            }
+            GameEvent::ActionTaken {
+                actor_idx, action, ..
+            } if *actor_idx != self.dealer_idx => {
+                if let Some(idx) = ACTIONS.iter().position(|known| known == action) {
+                    self.actions[idx] += 1;
+                }
+            }
+            GameEvent::InsuranceSettled { player, coins } => {
+                if let Some(bot) = self.bot(player) {
+                    bot.2 += *coins as i64;
+                }
+            }
+            GameEvent::HandSettled {
+                player,
+                result,
+                coins,
+                ..
+            } => {
+                if let Some(bot) = self.bot(player) {
+                    bot.2 += *coins as i64;
+                }
+                self.settled += 1;
+                match result {
+                    HandResult::AutoWin | HandResult::EvenMoney => {
+                        self.blackjacks += 1;
+                        self.wins += 1;
+                    }
+                    HandResult::Win => self.wins += 1,
+                    HandResult::Loss => self.losses += 1,
+                    HandResult::Draw => self.pushes += 1,
+                    HandResult::Surrender => {
+                        self.surrenders += 1;
+                        self.losses += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for SimulationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Rounds:       {}", self.rounds)?;
+        writeln!(f, "Hands:        {}", self.hands)?;
+        writeln!(f, "Shoes:        {}", self.shoes)?;
+        writeln!(f, "Wagered:      {}", self.wagered)?;
+        writeln!(f, "Net:          {:+}", self.net)?;
+        writeln!(f, "EV per hand:  {:+.4} coins", self.ev_per_hand())?;
+        writeln!(f, "House edge:   {:.3}%", self.house_edge())?;
+        writeln!(f, "Std dev:      {:.4} bets per hand", self.std_dev())?;
+        writeln!(f, "Wins:         {:.2}%", percent(self.wins, self.settled))?;
+        writeln!(
+            f,
+            "Losses:       {:.2}%",
+            percent(self.losses, self.settled)
+        )?;
+        writeln!(
+            f,
+            "Pushes:       {:.2}%",
+            percent(self.pushes, self.settled)
+        )?;
+        writeln!(
+            f,
+            "Blackjacks:   {:.2}%",
+            percent(self.blackjacks, self.settled)
+        )?;
+        writeln!(
+            f,
+            "Surrenders:   {:.2}%",
+            percent(self.surrenders, self.settled)
+        )?;
+        writeln!(f, "Actions:")?;
+        let decisions: u64 = self.actions.iter().sum();
+        for (action, count) in ACTIONS.iter().zip(self.actions.iter()) {
+            writeln!(
+                f,
+                "  {:<10}{:.2}%",
+                action.to_str(),
+                percent(*count, decisions)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays bots at one table until the configured number of hands or shoes is reached.
+pub fn simulate(config: &SimulationConfig) -> Result<SimulationStats, BlackjackError> {
+    let rules = config.rules;
+    let stats = Rc::new(RefCell::new(SimulationStats::default()));
+    let mut observers = Observers::default();
+    observers.register(Box::new(stats.clone()));
+
+    let mut strategies: Vec<Box<dyn Strategy>> = Vec::with_capacity(config.players);
+    for _ in 0..config.players {
+        match (config.system, &config.chart) {
+            (Some(system), _) => {
+                let strategy = CountingStrategy::new(system, &rules);
+                observers.register(Box::new(strategy.counter.clone()));
+                strategies.push(Box::new(strategy));
+            }
+            (None, Some(chart)) => strategies.push(Box::new(ChartStrategy::new(chart.clone()))),
+            (None, None) => strategies.push(Box::new(BasicStrategy::new(&rules))),
+        }
+    }
+
+    // deep enough pockets to never go broke
+    let buy_in = i32::MAX / 4;
+    let mut game = Game {
+        players: (0..config.players)
+            .map(|idx| Player::new(format!("Bot_{}", idx + 1), buy_in, rules.table_minimum))
+            .collect(),
+        buy_in,
+        shoe: Shoe::with_seed(rules.number_of_decks, rules.penetration, config.seed)?,
+        rules,
+        decisions: Box::new(TableDecisions::bots(rules, strategies)),
+        observers,
+    };
+
+    loop {
+        let done = {
+            let stats = stats.borrow();
+            match config.shoes {
+                Some(shoes) => stats.shoes >= shoes,
+                None => stats.hands + config.players as u64 > config.hands,
+            }
+        };
+        if done || !game.play_round()? {
+            break;
+        }
+        stats.borrow_mut().finish_round();
+    }
+
+    let stats = stats.borrow().clone();
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_the_requested_hands() {
+        let config = SimulationConfig {
+            rules: TableRules::default(),
+            seed: 42,
+            hands: 2000,
+            shoes: None,
+            players: 2,
+            system: None,
+            chart: None,
+        };
+        let stats = simulate(&config).unwrap();
+        assert_eq!(stats.hands, 2000);
+        assert_eq!(stats.rounds, 1000);
+        assert_eq!(stats.wagered, 2000 * config.rules.table_minimum as i64);
+        assert!(stats.settled >= stats.hands);
+        assert_eq!(stats.settled, stats.wins + stats.losses + stats.pushes);
+
+        let again = simulate(&config).unwrap();
+        assert_eq!(stats.net, again.net);
+    }
+}
@@ -0,0 +1,956 @@
+//! Exact expected values, worked out by recursion over the cards left in the shoe.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::cards::card::{Card, CardSymbol, Suit};
+use crate::error::BlackjackError;
+use crate::gameplay::blackjack::{blackjack_card_value, UserAction};
+use crate::gameplay::dealer::DealerPolicy;
+use crate::gameplay::hand::{Hand, HandValue};
+use crate::gameplay::rules::{HoleCardRule, NoHoleCardLoss, Surrender, TableRules};
+use crate::strategy::chart::{Chart, ChartAction, HARD_TOTALS, PAIRS, SOFT_TOTALS};
+
+/// Unseen cards by blackjack value, 2 to ace.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ShoeComposition {
+    pub counts: [u16; 10],
+}
+
+impl ShoeComposition {
+    pub fn from_decks(number_of_decks: usize) -> Self {
+        let decks = number_of_decks as u16;
+        let mut counts = [4 * decks; 10];
+        counts[8] = 16 * decks;
+        ShoeComposition { counts }
+    }
+
+    pub fn from_cards<'a>(cards: impl Iterator<Item = &'a Card>) -> Self {
+        let mut counts = [0; 10];
+        for card in cards {
+            let value = blackjack_card_value(&card.value);
+            if value >= 2 {
+                counts[(value - 2) as usize] += 1;
+            }
+        }
+        ShoeComposition { counts }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().map(|count| *count as u32).sum()
+    }
+
+    pub fn count(&self, value: i32) -> u16 {
+        self.counts[(value - 2) as usize]
+    }
+
+    /// Takes the card out of the shoe, e.g. once it was dealt.
+    pub fn remove(&mut self, card: &Card) -> Result<(), BlackjackError> {
+        let value = blackjack_card_value(&card.value);
+        if value < 2 || self.count(value) == 0 {
+            return Err(BlackjackError::OutOfCards);
+        }
+        self.counts[(value - 2) as usize] -= 1;
+        Ok(())
+    }
+
+    fn without(&self, value: i32) -> Self {
+        let mut shoe = *self;
+        shoe.counts[(value - 2) as usize] -= 1;
+        shoe
+    }
+}
+
+/// Expected value of every play in initial bets, `None` where the rules don't allow it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ActionValues {
+    pub stand: f64,
+    pub hit: Option<f64>,
+    pub double: Option<f64>,
+    pub split: Option<f64>,
+    pub surrender: Option<f64>,
+}
+
+impl ActionValues {
+    pub fn get(&self, action: UserAction) -> Option<f64> {
+        match action {
+            UserAction::Stay => Some(self.stand),
+            UserAction::Hit => self.hit,
+            UserAction::Double => self.double,
+            UserAction::Split => self.split,
+            UserAction::Surrender => self.surrender,
+        }
+    }
+
+    /// The play with the highest expected value, standing wins ties.
+    pub fn best(&self) -> (UserAction, f64) {
+        let mut best = (UserAction::Stay, self.stand);
+        for action in [
+            UserAction::Hit,
+            UserAction::Double,
+            UserAction::Split,
+            UserAction::Surrender,
+        ]
+        .iter()
+        {
+            if let Some(value) = self.get(*action) {
+                if value > best.1 {
+                    best = (*action, value);
+                }
+            }
+        }
+        best
+    }
+
+    /// Chart code of the best play, with the fallback for when a double or surrender is not
+    /// allowed.
+    pub fn chart_action(&self) -> ChartAction {
+        let hit = self.hit.unwrap_or(f64::NEG_INFINITY);
+        let play = self
+            .stand
+            .max(hit)
+            .max(self.double.unwrap_or(f64::NEG_INFINITY));
+        let hit_or_stand = |hit_action, stand_action| {
+            if hit > self.stand {
+                hit_action
+            } else {
+                stand_action
+            }
+        };
+        match self.best().0 {
+            UserAction::Split => ChartAction::Split,
+            UserAction::Surrender if self.split.map_or(false, |split| split > play) => {
+                ChartAction::SurrenderOrSplit
+            }
+            UserAction::Surrender => {
+                hit_or_stand(ChartAction::SurrenderOrHit, ChartAction::SurrenderOrStand)
+            }
+            UserAction::Double => hit_or_stand(ChartAction::Double, ChartAction::DoubleOrStand),
+            UserAction::Hit | UserAction::Stay => {
+                hit_or_stand(ChartAction::Hit, ChartAction::Stand)
+            }
+        }
+    }
+}
+
+/// `STAY +0.1234 HIT -0.0567 ...`, best play first.
+impl fmt::Display for ActionValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut values: Vec<(UserAction, f64)> = [
+            UserAction::Stay,
+            UserAction::Hit,
+            UserAction::Double,
+            UserAction::Split,
+            UserAction::Surrender,
+        ]
+        .iter()
+        .filter_map(|action| self.get(*action).map(|value| (*action, value)))
+        .collect();
+        values.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let values: Vec<String> = values
+            .iter()
+            .map(|(action, value)| format!("{} {:+.4}", action.to_str(), value))
+            .collect();
+        write!(f, "{}", values.join(" "))
+    }
+}
+
+/// Dealer final totals 17 to 21, bust and natural. Under the peek a natural never comes up, the
+/// odds only add up to the odds of the dealer not having one.
+type Outcomes = [f64; 7];
+
+/// Bets won or lost against every dealer outcome, in `Outcomes` order.
+type Payoffs = [i8; 7];
+
+/// A split hand once it got its second card, with the plays the rules leave it.
+struct SplitHand {
+    value: HandValue,
+    hit: bool,
+    double: bool,
+}
+
+/// A pair split with resplits up to `max_hands`.
+struct Split {
+    pair: i32,
+    /// The shoe left once the pair was split.
+    shoe: ShoeComposition,
+    /// By second card, 2 to ace.
+    hands: Vec<SplitHand>,
+    max_hands: usize,
+}
+
+/// Composition dependent expected values against a dealer up-card.
+///
+/// The player's draws are weighed with the dealer's hole card still in the shoe: under the peek
+/// only the hole cards that don't make a natural count, without a hole card the dealer's second
+/// card comes off what the player left.
+///
+/// Split hands are played one by one, each the best way for its own cards against the shoe left
+/// after the split. Cards dealt from a shuffled shoe are as likely in any order, so each hand's
+/// value is exact even though it is drawn from whatever the hands before it left. Resplits are
+/// taken whenever a split card comes up, up to `max_split_hands` or any lower limit that pays
+/// more. The shoe can't tell a ten of the pair's rank from the other tens, so ten pairs are only
+/// resplit when `split_unlike_tens` allows any two tens.
+pub struct Analyzer {
+    pub rules: TableRules,
+    dealer: DealerPolicy,
+    up: i32,
+    /// Dealer outcomes by the shoe the dealer draws from.
+    dealer_outcomes: HashMap<ShoeComposition, Outcomes>,
+    /// Dealer draws from the shoe being worked out, by the shoe left.
+    dealer_draws: HashMap<ShoeComposition, Outcomes>,
+    hits: HashMap<(ShoeComposition, i32, bool), f64>,
+    /// Split hands by the shoe they are played by, the shoe they draw from, their total, soft
+    /// and whether they can double.
+    split_hands: HashMap<(ShoeComposition, ShoeComposition, i32, bool, bool), f64>,
+    /// Split rounds of the current pair by the shoe left and the number of hands.
+    split_rounds: HashMap<(ShoeComposition, usize), f64>,
+}
+
+impl Analyzer {
+    pub fn new(rules: TableRules) -> Self {
+        Analyzer {
+            rules,
+            dealer: DealerPolicy::from_rules(&rules),
+            up: 0,
+            dealer_outcomes: HashMap::new(),
+            dealer_draws: HashMap::new(),
+            hits: HashMap::new(),
+            split_hands: HashMap::new(),
+            split_rounds: HashMap::new(),
+        }
+    }
+
+    /// Values of every play the rules allow for `hand`, `shoe` holds the unseen cards with the
+    /// hand and the up-card already taken out.
+    pub fn evaluate(
+        &mut self,
+        hand: &Hand,
+        dealer_up_card: &Card,
+        shoe: &ShoeComposition,
+    ) -> Result<ActionValues, BlackjackError> {
+        let up = blackjack_card_value(&dealer_up_card.value);
+        if up < 2 || hand.cards.is_empty() || shoe.total() == 0 {
+            return Err(BlackjackError::OutOfCards);
+        }
+        if up != self.up {
+            self.up = up;
+            self.dealer_outcomes.clear();
+            self.hits.clear();
+            self.split_hands.clear();
+        }
+
+        let value = HandValue::from_cards(hand.cards.iter())?;
+        let actions = hand.available_actions(&self.rules, 1);
+        let allowed = |action| actions.contains(&action);
+
+        let natural = self.natural_odds(shoe);
+        let with_natural = |loss: f64, value: f64| natural * loss + (1.0 - natural) * value;
+        // the peek takes the original bet of a natural before the player draws
+        let peek = self.rules.hole_card == HoleCardRule::Peek;
+        let drawn = |value: f64| if peek { value - natural } else { value };
+
+        let stand = if hand.is_natural() {
+            let payout = self.rules.blackjack_payout.pay(1000) as f64 / 1000.0;
+            with_natural(0.0, payout)
+        } else {
+            drawn(self.stand_value(value, shoe))
+        };
+        let hit = if allowed(UserAction::Hit) {
+            Some(drawn(self.hit_value(value, shoe)))
+        } else {
+            None
+        };
+        let double = if allowed(UserAction::Double) {
+            Some(drawn(self.double_value(value, shoe)))
+        } else {
+            None
+        };
+        let split = if allowed(UserAction::Split) {
+            let pair = blackjack_card_value(&hand.card_at(0)?.value);
+            Some(drawn(self.split_value(pair, shoe)?))
+        } else {
+            None
+        };
+        let surrender = if allowed(UserAction::Surrender) {
+            match self.rules.surrender {
+                Surrender::Early => Some(-0.5),
+                _ => Some(with_natural(-1.0, -0.5)),
+            }
+        } else {
+            None
+        };
+
+        Ok(ActionValues {
+            stand,
+            hit,
+            double,
+            split,
+            surrender,
+        })
+    }
+
+    /// The best play for every chart cell off a full shoe. Hard totals weigh every two card
+    /// hand of the total by its odds, hard 20 and 21 are looked at as three card hands.
+    pub fn chart(&mut self) -> Result<Chart, BlackjackError> {
+        let full_shoe = ShoeComposition::from_decks(self.rules.number_of_decks);
+        let mut chart = Chart::new();
+        for up in 2..=11 {
+            let column = (up - 2) as usize;
+            let up_card = card(up)?;
+            let mut cell = |cards: &[i32]| -> Result<ActionValues, BlackjackError> {
+                let mut shoe = full_shoe;
+                shoe.remove(&up_card)?;
+                let mut hand = Hand::new();
+                for value in cards {
+                    let card = card(*value)?;
+                    shoe.remove(&card)?;
+                    hand.deal_card(card);
+                }
+                hand.update_state()?;
+                self.evaluate(&hand, &up_card, &shoe)
+            };
+
+            for total in HARD_TOTALS {
+                let mut hands = Vec::new();
+                for low in 2..=(total - 1) / 2 {
+                    let high = total - low;
+                    if high <= 10 {
+                        let odds = full_shoe.count(low) as f64 * full_shoe.count(high) as f64;
+                        hands.push((odds, cell(&[low, high])?));
+                    }
+                }
+                let values = if hands.is_empty() {
+                    cell(&[10, 6, total - 16])?
+                } else {
+                    weighted(&hands)
+                };
+                chart.hard_mut(total)[column] = values.chart_action();
+            }
+            for total in SOFT_TOTALS {
+                chart.soft_mut(total)[column] = cell(&[11, total - 11])?.chart_action();
+            }
+            for value in PAIRS {
+                chart.pair_mut(value)[column] = cell(&[value, value])?.chart_action();
+            }
+        }
+        Ok(chart)
+    }
+
+    fn natural_odds(&self, shoe: &ShoeComposition) -> f64 {
+        let natural_card = match self.up {
+            11 => 10,
+            10 => 11,
+            _ => return 0.0,
+        };
+        match shoe.total() {
+            0 => 0.0,
+            total => shoe.count(natural_card) as f64 / total as f64,
+        }
+    }
+
+    /// Bets a hand of `bet` loses to a dealer natural turned up after the players.
+    fn natural_loss(&self, bet: i8) -> i8 {
+        match self.rules.hole_card {
+            HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly) => 1,
+            _ => bet,
+        }
+    }
+
+    /// What the payoffs are worth once the player is done and the dealer plays from `shoe`.
+    fn settle(&mut self, shoe: &ShoeComposition, payoffs: &Payoffs) -> f64 {
+        if payoffs[..6].iter().all(|payoff| *payoff == payoffs[0]) {
+            // a bust, the dealer's cards only matter for a natural
+            let natural = self.natural_odds(shoe);
+            let natural_payoff = match self.rules.hole_card {
+                HoleCardRule::Peek => 0.0,
+                HoleCardRule::NoHoleCard(_) => natural * payoffs[6] as f64,
+            };
+            return (1.0 - natural) * payoffs[0] as f64 + natural_payoff;
+        }
+
+        let outcomes = self.dealer_outcomes(shoe);
+        outcomes
+            .iter()
+            .zip(payoffs.iter())
+            .map(|(odds, payoff)| odds * *payoff as f64)
+            .sum()
+    }
+
+    fn stand_value(&mut self, value: HandValue, shoe: &ShoeComposition) -> f64 {
+        let payoffs = hand_payoffs(value, 1, self.natural_loss(1));
+        self.settle(shoe, &payoffs)
+    }
+
+    /// Hit and keep playing the best of hit or stand.
+    fn hit_value(&mut self, value: HandValue, shoe: &ShoeComposition) -> f64 {
+        let key = (*shoe, value.total, value.soft);
+        if let Some(ev) = self.hits.get(&key) {
+            return *ev;
+        }
+
+        let total = shoe.total() as f64;
+        let mut ev = 0.0;
+        for card in 2..=11 {
+            let count = shoe.count(card);
+            if count == 0 {
+                continue;
+            }
+            let next = add_card(value, card);
+            let shoe = shoe.without(card);
+            let play = if next.total >= 21 {
+                self.stand_value(next, &shoe)
+            } else {
+                let stand = self.stand_value(next, &shoe);
+                stand.max(self.hit_value(next, &shoe))
+            };
+            ev += count as f64 / total * play;
+        }
+
+        self.hits.insert(key, ev);
+        ev
+    }
+
+    fn double_value(&mut self, value: HandValue, shoe: &ShoeComposition) -> f64 {
+        let natural_loss = self.natural_loss(2);
+        let total = shoe.total() as f64;
+        let mut ev = 0.0;
+        for card in 2..=11 {
+            let count = shoe.count(card);
+            if count > 0 {
+                let payoffs = hand_payoffs(add_card(value, card), 2, natural_loss);
+                ev += count as f64 / total * self.settle(&shoe.without(card), &payoffs);
+            }
+        }
+        ev
+    }
+
+    /// The best of resplitting up to every limit the rules allow.
+    fn split_value(&mut self, pair: i32, shoe: &ShoeComposition) -> Result<f64, BlackjackError> {
+        let resplit = match pair {
+            11 => self.rules.resplit_aces,
+            10 => self.rules.split_unlike_tens,
+            _ => true,
+        };
+        let max_hands = if resplit {
+            self.rules.max_split_hands.max(2)
+        } else {
+            2
+        };
+
+        let mut hands = Vec::new();
+        for second in 2..=11 {
+            let mut hand = Hand::from_cards(vec![card(pair)?, card(second)?]);
+            hand.split = true;
+            hand.update_state()?;
+            let actions = hand.available_actions(&self.rules, self.rules.max_split_hands);
+            hands.push(SplitHand {
+                value: hand.value,
+                hit: actions.contains(&UserAction::Hit),
+                double: actions.contains(&UserAction::Double),
+            });
+        }
+        let mut split = Split {
+            pair,
+            shoe: *shoe,
+            hands,
+            max_hands: 2,
+        };
+
+        let mut best = f64::MIN;
+        for max_hands in 2..=max_hands {
+            split.max_hands = max_hands;
+            self.split_rounds.clear();
+            best = best.max(self.split_round(&split, shoe, 2));
+        }
+        // a natural takes the original bet only, not a bet on every split hand
+        let natural = match self.rules.hole_card {
+            HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly) => self.natural_odds(shoe),
+            _ => 0.0,
+        };
+        Ok(best - natural)
+    }
+
+    /// Deals the next split hand its second card, resplitting on a split card, and plays it out
+    /// before the hands after it are dealt to. The order the cards are dealt in doesn't change
+    /// the odds of any hand or of the dealer, so each hand can draw and the dealer play out right
+    /// after it, from the shoe left by the second cards dealt so far.
+    fn split_round(&mut self, split: &Split, shoe: &ShoeComposition, hands: usize) -> f64 {
+        let key = (*shoe, hands);
+        if let Some(ev) = self.split_rounds.get(&key) {
+            return *ev;
+        }
+
+        let dealt = (split.shoe.total() - shoe.total()) as usize - (hands - 2);
+        let total = shoe.total() as f64;
+        let mut ev = 0.0;
+        for (card, hand) in (2..=11).zip(split.hands.iter()) {
+            let count = shoe.count(card);
+            if count == 0 {
+                continue;
+            }
+            let next = shoe.without(card);
+            let play = if card == split.pair && hands < split.max_hands {
+                self.split_round(split, &next, hands + 1)
+            } else {
+                let known = split.shoe.without(card);
+                let value = self.split_hand(hand.value, hand.hit, hand.double, &known, &next);
+                if dealt + 1 < hands {
+                    value + self.split_round(split, &next, hands)
+                } else {
+                    value
+                }
+            };
+            ev += count as f64 / total * play;
+        }
+
+        self.split_rounds.insert(key, ev);
+        ev
+    }
+
+    /// `known` is the shoe the hand is played by, `shoe` the one it draws from.
+    fn split_hand(
+        &mut self,
+        value: HandValue,
+        hit: bool,
+        double: bool,
+        known: &ShoeComposition,
+        shoe: &ShoeComposition,
+    ) -> f64 {
+        let hole_card = self.rules.hole_card;
+        let natural_loss = |bet| match hole_card {
+            // taken once for the whole split by `split_value`
+            HoleCardRule::NoHoleCard(NoHoleCardLoss::OriginalBetsOnly) => 0,
+            _ => bet,
+        };
+        let stand = hand_payoffs(value, 1, natural_loss(1));
+        if value.total >= 21 || !hit {
+            return self.settle(shoe, &stand);
+        }
+        let key = (*known, *shoe, value.total, value.soft, double);
+        if let Some(ev) = self.split_hands.get(&key) {
+            return *ev;
+        }
+
+        let stand_value = self.stand_value(value, known);
+        let hit_value = self.hit_value(value, known);
+        let double_value = if double {
+            self.double_value(value, known)
+        } else {
+            f64::MIN
+        };
+
+        let total = shoe.total() as f64;
+        let mut ev = 0.0;
+        if double_value > stand_value.max(hit_value) {
+            for card in 2..=11 {
+                let count = shoe.count(card);
+                if count > 0 {
+                    let payoffs = hand_payoffs(add_card(value, card), 2, natural_loss(2));
+                    ev += count as f64 / total * self.settle(&shoe.without(card), &payoffs);
+                }
+            }
+        } else if stand_value >= hit_value {
+            ev = self.settle(shoe, &stand);
+        } else {
+            for card in 2..=11 {
+                let count = shoe.count(card);
+                if count > 0 {
+                    let next = add_card(value, card);
+                    let known = known.without(card);
+                    let play = self.split_hand(next, true, false, &known, &shoe.without(card));
+                    ev += count as f64 / total * play;
+                }
+            }
+        }
+
+        self.split_hands.insert(key, ev);
+        ev
+    }
+
+    fn dealer_outcomes(&mut self, shoe: &ShoeComposition) -> Outcomes {
+        if let Some(outcomes) = self.dealer_outcomes.get(shoe) {
+            return *outcomes;
+        }
+
+        let natural_card = match self.up {
+            11 => 10,
+            10 => 11,
+            _ => 0,
+        };
+        let start = add_card(
+            HandValue {
+                total: 0,
+                soft: false,
+            },
+            self.up,
+        );
+        let total = shoe.total() as f64;
+        let mut outcomes = [0.0; 7];
+        for value in 2..=11 {
+            let count = shoe.count(value);
+            if count == 0 {
+                continue;
+            }
+            let probability = count as f64 / total;
+            if value != natural_card {
+                let next = self.dealer_draw(add_card(start, value), &shoe.without(value));
+                for (sum, odds) in outcomes.iter_mut().zip(next.iter()) {
+                    *sum += probability * odds;
+                }
+            } else if self.rules.hole_card != HoleCardRule::Peek {
+                outcomes[6] += probability;
+            }
+        }
+
+        self.dealer_draws.clear();
+        self.dealer_outcomes.insert(*shoe, outcomes);
+        outcomes
+    }
+
+    /// Odds of every dealer final total once the dealer got to `value` with `shoe` left. The
+    /// shoe tells the cards the dealer drew, in whatever order.
+    fn dealer_draw(&mut self, value: HandValue, shoe: &ShoeComposition) -> Outcomes {
+        let mut outcomes = [0.0; 7];
+        if value.total > 21 {
+            outcomes[5] = 1.0;
+            return outcomes;
+        }
+        if self.dealer.action(&value) == UserAction::Stay {
+            outcomes[(value.total - 17) as usize] = 1.0;
+            return outcomes;
+        }
+        if let Some(outcomes) = self.dealer_draws.get(shoe) {
+            return *outcomes;
+        }
+
+        let total = shoe.total() as f64;
+        for card in 2..=11 {
+            let count = shoe.count(card);
+            if count > 0 {
+                let next = self.dealer_draw(add_card(value, card), &shoe.without(card));
+                for (sum, odds) in outcomes.iter_mut().zip(next.iter()) {
+                    *sum += count as f64 / total * odds;
+                }
+            }
+        }
+
+        self.dealer_draws.insert(*shoe, outcomes);
+        outcomes
+    }
+}
+
+/// Bets won or lost by a hand standing on `value`, a bust loses against every dealer outcome.
+fn hand_payoffs(value: HandValue, bet: i8, natural_loss: i8) -> Payoffs {
+    let mut payoffs = [-bet; 7];
+    if value.total <= 21 {
+        for (idx, payoff) in payoffs[..5].iter_mut().enumerate() {
+            *payoff = match value.total.cmp(&(17 + idx as i32)) {
+                Ordering::Greater => bet,
+                Ordering::Less => -bet,
+                Ordering::Equal => 0,
+            };
+        }
+        payoffs[5] = bet;
+    }
+    payoffs[6] = -natural_loss;
+    payoffs
+}
+
+/// Average of the values weighted by the odds, the hands allow the same plays.
+fn weighted(values: &[(f64, ActionValues)]) -> ActionValues {
+    let odds: f64 = values.iter().map(|(odds, _)| odds).sum();
+    let average = |value: &dyn Fn(&ActionValues) -> Option<f64>| -> Option<f64> {
+        values
+            .iter()
+            .map(|(weight, values)| value(values).map(|value| weight * value / odds))
+            .sum()
+    };
+    ActionValues {
+        stand: average(&|values| Some(values.stand)).unwrap_or(0.0),
+        hit: average(&|values| values.hit),
+        double: average(&|values| values.double),
+        split: average(&|values| values.split),
+        surrender: average(&|values| values.surrender),
+    }
+}
+
+/// Value of the hand with one more card, `card` by blackjack value with the ace as 11.
+fn add_card(value: HandValue, card: i32) -> HandValue {
+    let counted = if card == 11 && value.total + 11 > 21 {
+        1
+    } else {
+        card
+    };
+    let mut total = value.total + counted;
+    let mut soft = value.soft || counted == 11;
+    if total > 21 && soft {
+        total -= 10;
+        soft = false;
+    }
+    HandValue { total, soft }
+}
+
+/// A card of the blackjack value, ace as 11.
+fn card(value: i32) -> Result<Card, BlackjackError> {
+    let symbol = CardSymbol::from_value(if value == 11 { 1 } else { value })?;
+    Ok(Card::new(Suit::Spade, symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::deck::Deck;
+    use crate::cards::shoe::Shoe;
+    use crate::gameplay::event::Observers;
+    use crate::gameplay::game::Game;
+    use crate::gameplay::player::Player;
+    use crate::strategy::{Strategy, TableDecisions};
+
+    fn evaluate(rules: TableRules, hand: &str, up: &str) -> ActionValues {
+        let hand: Hand = hand.parse().unwrap();
+        let up: Card = up.parse().unwrap();
+        let mut shoe = ShoeComposition::from_decks(rules.number_of_decks);
+        for card in hand.cards.iter().chain(std::iter::once(&up)) {
+            shoe.remove(card).unwrap();
+        }
+        Analyzer::new(rules).evaluate(&hand, &up, &shoe).unwrap()
+    }
+
+    #[test]
+    fn values_the_textbook_plays() {
+        let rules = TableRules::default();
+
+        let hard_16 = evaluate(rules, "10S 6H", "10D");
+        assert_eq!(hard_16.best().0, UserAction::Hit);
+        assert!(hard_16.stand - hard_16.hit.unwrap() < 0.01);
+        assert_eq!(hard_16.surrender, None);
+
+        assert_eq!(
+            evaluate(rules, "6S 5H", "6D").chart_action(),
+            ChartAction::Double
+        );
+        assert_eq!(
+            evaluate(rules, "AS 7H", "3D").chart_action(),
+            ChartAction::DoubleOrStand
+        );
+        assert_eq!(
+            evaluate(rules, "8S 8H", "10D").chart_action(),
+            ChartAction::Split
+        );
+        assert_eq!(
+            evaluate(rules, "9S 3H", "4D").chart_action(),
+            ChartAction::Stand
+        );
+
+        let natural = evaluate(rules, "AS KH", "6D");
+        assert!((natural.stand - 1.5).abs() < 1e-9);
+
+        let surrender = TableRules {
+            surrender: Surrender::Late,
+            ..rules
+        };
+        let hard_16 = evaluate(surrender, "10S 6H", "10D");
+        assert_eq!(hard_16.chart_action(), ChartAction::SurrenderOrHit);
+    }
+
+    #[test]
+    fn dealer_natural_left_as_the_only_hole_card() {
+        let deck: Deck = "10S KH QC JD".parse().unwrap();
+        let shoe = ShoeComposition::from_cards(deck.cards.iter());
+        let hand: Hand = "9S 8H".parse().unwrap();
+        let up: Card = "AD".parse().unwrap();
+        let values = Analyzer::new(TableRules::default())
+            .evaluate(&hand, &up, &shoe)
+            .unwrap();
+        assert!((values.stand + 1.0).abs() < 1e-9);
+        assert!(values.hit.map_or(true, f64::is_finite));
+        assert!(values.double.map_or(true, f64::is_finite));
+    }
+
+    /// Splits whenever it can, then plays each hand the way the analyzer values it: the best
+    /// play for the hand's own cards against the shoe left after the split.
+    struct SplitHands {
+        analyzer: Analyzer,
+        split: ShoeComposition,
+    }
+
+    impl Strategy for SplitHands {
+        fn action(
+            &mut self,
+            hand: &Hand,
+            dealer_up_card: &Card,
+            _rules: &TableRules,
+            actions: &[UserAction],
+        ) -> UserAction {
+            if actions.contains(&UserAction::Split) {
+                return UserAction::Split;
+            }
+            let mut known = self.split;
+            for card in hand.cards.iter().skip(1) {
+                known.remove(card).unwrap();
+            }
+            let values = self
+                .analyzer
+                .evaluate(hand, dealer_up_card, &known)
+                .unwrap();
+            let hit = values.hit.unwrap_or(f64::MIN);
+            match values.double {
+                Some(double) if double > values.stand.max(hit) => UserAction::Double,
+                _ if values.stand >= hit => UserAction::Stay,
+                _ => UserAction::Hit,
+            }
+        }
+    }
+
+    fn permutations(cards: &[Card]) -> Vec<Vec<Card>> {
+        if cards.is_empty() {
+            return vec![Vec::new()];
+        }
+        let mut permutations = Vec::new();
+        for idx in 0..cards.len() {
+            let mut rest = cards.to_vec();
+            let card = rest.remove(idx);
+            for mut permutation in self::permutations(&rest) {
+                permutation.insert(0, card.clone());
+                permutations.push(permutation);
+            }
+        }
+        permutations
+    }
+
+    /// Takes `action` on the first two cards and stands after.
+    struct Once(UserAction);
+
+    impl Strategy for Once {
+        fn action(
+            &mut self,
+            hand: &Hand,
+            _dealer_up_card: &Card,
+            _rules: &TableRules,
+            actions: &[UserAction],
+        ) -> UserAction {
+            if hand.cards.len() == 2 && actions.contains(&self.0) {
+                self.0
+            } else {
+                UserAction::Stay
+            }
+        }
+    }
+
+    /// Average bets won by `strategy` over every order the `shoe` can come out in, dealt through
+    /// the game after the hand and the up-card.
+    fn play_through_the_game(
+        rules: TableRules,
+        hand: &str,
+        up: &str,
+        shoe: &str,
+        strategy: impl Fn() -> Box<dyn Strategy>,
+    ) -> f64 {
+        let shoe: Deck = shoe.parse().unwrap();
+        let cards: Vec<Card> = shoe.cards.iter().cloned().collect();
+        let orders = permutations(&cards);
+        let mut coins = 0;
+        for order in orders.iter() {
+            let mut deck: Deck = format!("{} {}", hand, up).parse().unwrap();
+            deck.cards.extend(order.iter().cloned());
+            let mut game = Game {
+                players: vec![Player::new("P1".to_string(), 1000, 10)],
+                buy_in: 1000,
+                shoe: Shoe::new(rules.number_of_decks, rules.penetration).unwrap(),
+                rules,
+                decisions: Box::new(TableDecisions::bots(rules, vec![strategy()])),
+                observers: Observers::default(),
+            };
+            game.shoe.stack(deck);
+            assert!(game.play_round().unwrap());
+            coins += game.players[0].bankroll - 1000;
+        }
+        coins as f64 / 10.0 / orders.len() as f64
+    }
+
+    fn analyze(rules: TableRules, hand: &str, up: &str, shoe: &str) -> ActionValues {
+        let hand: Hand = hand.parse().unwrap();
+        let up: Card = up.parse().unwrap();
+        let shoe = ShoeComposition::from_cards(shoe.parse::<Deck>().unwrap().cards.iter());
+        Analyzer::new(rules).evaluate(&hand, &up, &shoe).unwrap()
+    }
+
+    fn assert_played(analyzed: f64, played: f64) {
+        assert!(
+            (analyzed - played).abs() < 1e-9,
+            "{} analyzed, {} played",
+            analyzed,
+            played
+        );
+    }
+
+    #[test]
+    fn weighs_the_draws_by_the_hole_cards_left_exactly() {
+        let rules = TableRules::default();
+        let shoe = "KC 9S 5D 10H 2C 7D";
+        let values = analyze(rules, "10S 7H", "AD", shoe);
+        let played = play_through_the_game(rules, "10S 7H", "AD", shoe, || {
+            Box::new(Once(UserAction::Stay))
+        });
+        assert_played(values.stand, played);
+
+        let values = analyze(rules, "6S 5H", "AD", shoe);
+        let played = play_through_the_game(rules, "6S 5H", "AD", shoe, || {
+            Box::new(Once(UserAction::Double))
+        });
+        assert_played(values.double.unwrap(), played);
+    }
+
+    /// The analyzer's split value against the best resplit limit played through the game.
+    fn assert_exact_split(rules: TableRules, pair: &str, up: &str, shoe: &str) {
+        let split = ShoeComposition::from_cards(shoe.parse::<Deck>().unwrap().cards.iter());
+        let mut best = f64::MIN;
+        for max_split_hands in 2..=rules.max_split_hands {
+            let rules = TableRules {
+                max_split_hands,
+                ..rules
+            };
+            let played = play_through_the_game(rules, pair, up, shoe, || {
+                Box::new(SplitHands {
+                    analyzer: Analyzer::new(rules),
+                    split,
+                })
+            });
+            best = best.max(played);
+        }
+        assert_played(analyze(rules, pair, up, shoe).split.unwrap(), best);
+    }
+
+    #[test]
+    fn resplits_aces_under_the_peek_exactly() {
+        let rules = TableRules {
+            resplit_aces: true,
+            max_split_hands: 3,
+            ..TableRules::default()
+        };
+        assert_exact_split(rules, "AS AH", "10D", "AC 9S 10S KH 7C 8D");
+    }
+
+    #[test]
+    fn plays_split_hands_from_the_shoe_the_others_left_exactly() {
+        let rules = TableRules {
+            max_split_hands: 3,
+            ..TableRules::default()
+        };
+        assert_exact_split(rules, "8S 8H", "6D", "8C 3S 10S KH 9C 5D QH");
+    }
+
+    #[test]
+    fn splits_against_a_natural_turned_up_after_the_players_exactly() {
+        for loss in [NoHoleCardLoss::OriginalBetsOnly, NoHoleCardLoss::AllBets] {
+            let rules = TableRules {
+                hole_card: HoleCardRule::NoHoleCard(loss),
+                max_split_hands: 2,
+                ..TableRules::default()
+            };
+            assert_exact_split(rules, "8S 8H", "10D", "AC 3S 10S 9H 7C KD QH");
+        }
+    }
+}
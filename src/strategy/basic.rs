@@ -0,0 +1,196 @@
+use crate::cards::card::Card;
+use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::hand::Hand;
+use crate::gameplay::rules::{DoubleRule, HoleCardRule, NoHoleCardLoss, Surrender, TableRules};
+use crate::strategy::chart::{Chart, ChartAction, HARD_TOTALS, PAIRS, SOFT_TOTALS};
+use crate::strategy::Strategy;
+
+/// Plays the basic strategy chart for the table rules, never takes insurance.
+pub struct BasicStrategy {
+    pub chart: Chart,
+}
+
+impl BasicStrategy {
+    pub fn new(rules: &TableRules) -> Self {
+        BasicStrategy {
+            chart: basic_strategy_chart(rules),
+        }
+    }
+}
+
+impl Strategy for BasicStrategy {
+    fn action(
+        &mut self,
+        hand: &Hand,
+        dealer_up_card: &Card,
+        _rules: &TableRules,
+        actions: &[UserAction],
+    ) -> UserAction {
+        self.chart.action(hand, dealer_up_card, actions)
+    }
+
+    fn early_surrender(&mut self, hand: &Hand, dealer_up_card: &Card, _rules: &TableRules) -> bool {
+        self.chart.surrenders(hand, dealer_up_card)
+    }
+}
+
+/// The usual basic strategy charts, adjusted for soft 17, number of decks, doubling after
+/// splits, surrender and the European no hole card rule.
+pub fn basic_strategy_chart(rules: &TableRules) -> Chart {
+    let mut chart = Chart::new();
+    for up in 2..=11 {
+        let column = (up - 2) as usize;
+        for total in HARD_TOTALS {
+            chart.hard_mut(total)[column] = no_hole_card(hard(total, up, rules), up, rules);
+        }
+        for total in SOFT_TOTALS {
+            chart.soft_mut(total)[column] = no_hole_card(soft(total, up, rules), up, rules);
+        }
+        for value in PAIRS {
+            let mut action = no_hole_card(pair(value, up, rules), up, rules);
+            if value == 11 && up == 10 && action == ChartAction::Hit {
+                // aces are still split against a ten without a hole card
+                action = ChartAction::Split;
+            }
+            chart.pair_mut(value)[column] = action;
+        }
+    }
+    chart
+}
+
+fn hard(total: i32, up: i32, rules: &TableRules) -> ChartAction {
+    let few_decks = rules.number_of_decks <= 2;
+    if surrenders(total, up, rules) {
+        return if total >= 17 {
+            ChartAction::SurrenderOrStand
+        } else {
+            ChartAction::SurrenderOrHit
+        };
+    }
+
+    match total {
+        17..=21 => ChartAction::Stand,
+        13..=16 if up <= 6 => ChartAction::Stand,
+        12 if (4..=6).contains(&up) => ChartAction::Stand,
+        11 if up <= 10 || rules.dealer_hits_soft_17 || few_decks => ChartAction::Double,
+        10 if up <= 9 => ChartAction::Double,
+        9 if (3..=6).contains(&up) || (few_decks && up == 2) => ChartAction::Double,
+        8 if rules.number_of_decks == 1 && (5..=6).contains(&up) => ChartAction::Double,
+        _ => ChartAction::Hit,
+    }
+}
+
+fn soft(total: i32, up: i32, rules: &TableRules) -> ChartAction {
+    let h17 = rules.dealer_hits_soft_17;
+    match total {
+        13 | 14 if (5..=6).contains(&up) => ChartAction::Double,
+        15 | 16 if (4..=6).contains(&up) => ChartAction::Double,
+        17 if (3..=6).contains(&up) => ChartAction::Double,
+        18 => match up {
+            2 if h17 => ChartAction::DoubleOrStand,
+            3..=6 => ChartAction::DoubleOrStand,
+            2 | 7 | 8 => ChartAction::Stand,
+            _ => ChartAction::Hit,
+        },
+        19 if h17 && up == 6 => ChartAction::DoubleOrStand,
+        19..=21 => ChartAction::Stand,
+        _ => ChartAction::Hit,
+    }
+}
+
+fn pair(value: i32, up: i32, rules: &TableRules) -> ChartAction {
+    let das = rules.double_after_split && rules.double != DoubleRule::NotAllowed;
+    let split = match value {
+        11 | 8 => true,
+        9 => (2..=6).contains(&up) || (8..=9).contains(&up),
+        7 => up <= 7,
+        6 if das => up <= 6,
+        6 => (3..=6).contains(&up),
+        4 => das && (5..=6).contains(&up),
+        2 | 3 if das => up <= 7,
+        2 | 3 => (4..=7).contains(&up),
+        _ => false,
+    };
+
+    if !split {
+        return hard(value * 2, up, rules);
+    }
+    if value == 8 && up == 11 && surrenders(16, up, rules) && rules.dealer_hits_soft_17 {
+        return ChartAction::SurrenderOrSplit;
+    }
+    ChartAction::Split
+}
+
+/// Late surrender spots, early surrender adds the big ones against an ace or a ten.
+fn surrenders(total: i32, up: i32, rules: &TableRules) -> bool {
+    let late = match (total, up) {
+        (16, 10) | (16, 11) | (15, 10) => true,
+        (16, 9) => rules.number_of_decks > 2,
+        (15, 11) | (17, 11) => rules.dealer_hits_soft_17,
+        _ => false,
+    };
+    match rules.surrender {
+        Surrender::NotAllowed => false,
+        Surrender::Late => late,
+        Surrender::Early => match up {
+            11 => (5..=7).contains(&total) || (12..=17).contains(&total),
+            10 => (14..=16).contains(&total),
+            _ => late,
+        },
+    }
+}
+
+/// Without a hole card doubles and splits against a ten or an ace are lost to a dealer
+/// blackjack as well, so they are played as a hit or a stand instead.
+fn no_hole_card(action: ChartAction, up: i32, rules: &TableRules) -> ChartAction {
+    if rules.hole_card != HoleCardRule::NoHoleCard(NoHoleCardLoss::AllBets) || up < 10 {
+        return action;
+    }
+    match action {
+        ChartAction::Double | ChartAction::Split => ChartAction::Hit,
+        ChartAction::DoubleOrStand => ChartAction::Stand,
+        ChartAction::SurrenderOrSplit => ChartAction::SurrenderOrHit,
+        _ => action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(rules: &TableRules, hand: &str, up: &str, actions: &[UserAction]) -> UserAction {
+        let hand: Hand = hand.parse().unwrap();
+        let up: Card = up.parse().unwrap();
+        BasicStrategy::new(rules).action(&hand, &up, rules, actions)
+    }
+
+    #[test]
+    fn follows_the_chart_for_the_table_rules() {
+        use UserAction::{Double, Hit, Split, Stay};
+        let all = [Stay, Hit, Double, Split, UserAction::Surrender];
+        let no_double = [Stay, Hit, Split, UserAction::Surrender];
+        let s17 = TableRules::default();
+        let h17 = TableRules {
+            dealer_hits_soft_17: true,
+            surrender: Surrender::Late,
+            ..TableRules::default()
+        };
+        let enhc = TableRules {
+            hole_card: HoleCardRule::NoHoleCard(NoHoleCardLoss::AllBets),
+            ..TableRules::default()
+        };
+
+        assert_eq!(play(&s17, "6S 5H", "AD", &all), Hit);
+        assert_eq!(play(&h17, "6S 5H", "AD", &all), Double);
+        assert_eq!(play(&enhc, "6S 5H", "KD", &all), Hit);
+        assert_eq!(play(&s17, "6S 5H", "KD", &no_double), Hit);
+        assert_eq!(play(&s17, "AS 7H", "4D", &no_double), Stay);
+        assert_eq!(play(&s17, "10S 6H", "KD", &all), Hit);
+        assert_eq!(play(&h17, "10S 6H", "KD", &all), UserAction::Surrender);
+        assert_eq!(play(&h17, "8S 8H", "AD", &all), UserAction::Surrender);
+        assert_eq!(play(&s17, "8S 8H", "AD", &all), Split);
+        assert_eq!(play(&s17, "8S 8H", "AD", &[Stay, Hit]), Hit);
+        assert_eq!(play(&s17, "KS QH", "6D", &all), Stay);
+        assert_eq!(play(&s17, "AS AH", "6D", &[Stay, Hit]), Hit);
+    }
+}
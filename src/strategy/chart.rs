@@ -0,0 +1,555 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::cards::card::Card;
+use crate::error::BlackjackError;
+use crate::gameplay::blackjack::{blackjack_card_value, UserAction};
+use crate::gameplay::hand::Hand;
+use crate::gameplay::rules::TableRules;
+use crate::strategy::Strategy;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseChartError {
+    /// Unknown play code.
+    Action { line: usize, code: String },
+    /// Unknown section heading, row label or dealer up-card.
+    Label { line: usize, label: String },
+    /// A row before any section heading, or without a cell per dealer up-card.
+    Row { line: usize },
+    /// A row of the chart is not in the file.
+    Missing(String),
+}
+
+impl fmt::Display for ParseChartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseChartError::Action { line, code } => {
+                write!(f, "line {}: invalid chart action {:?}!", line, code)
+            }
+            ParseChartError::Label { line, label } => {
+                write!(f, "line {}: invalid chart label {:?}!", line, label)
+            }
+            ParseChartError::Row { line } => write!(f, "line {}: invalid chart row!", line),
+            ParseChartError::Missing(row) => write!(f, "chart row {} is missing!", row),
+        }
+    }
+}
+
+impl Error for ParseChartError {}
+
+/// One cell of a strategy chart, codes as printed on the usual charts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChartAction {
+    /// `H`
+    Hit,
+    /// `S`
+    Stand,
+    /// `D`, double if allowed, otherwise hit.
+    Double,
+    /// `Ds`, double if allowed, otherwise stand.
+    DoubleOrStand,
+    /// `P`
+    Split,
+    /// `Rh`, surrender if allowed, otherwise hit.
+    SurrenderOrHit,
+    /// `Rs`, surrender if allowed, otherwise stand.
+    SurrenderOrStand,
+    /// `Rp`, surrender if allowed, otherwise split.
+    SurrenderOrSplit,
+}
+
+impl ChartAction {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            ChartAction::Hit => "H",
+            ChartAction::Stand => "S",
+            ChartAction::Double => "D",
+            ChartAction::DoubleOrStand => "Ds",
+            ChartAction::Split => "P",
+            ChartAction::SurrenderOrHit => "Rh",
+            ChartAction::SurrenderOrStand => "Rs",
+            ChartAction::SurrenderOrSplit => "Rp",
+        }
+    }
+
+    pub fn is_surrender(self) -> bool {
+        matches!(
+            self,
+            ChartAction::SurrenderOrHit
+                | ChartAction::SurrenderOrStand
+                | ChartAction::SurrenderOrSplit
+        )
+    }
+
+    /// First choice that is in `actions`, `None` when a split is not available and the hand
+    /// should be played by its total instead.
+    fn resolve(self, actions: &[UserAction]) -> Option<UserAction> {
+        let allowed = |action: UserAction| actions.contains(&action);
+        match self {
+            ChartAction::Hit => Some(UserAction::Hit),
+            ChartAction::Stand => Some(UserAction::Stay),
+            ChartAction::Double if allowed(UserAction::Double) => Some(UserAction::Double),
+            ChartAction::Double => Some(UserAction::Hit),
+            ChartAction::DoubleOrStand if allowed(UserAction::Double) => Some(UserAction::Double),
+            ChartAction::DoubleOrStand => Some(UserAction::Stay),
+            ChartAction::Split if allowed(UserAction::Split) => Some(UserAction::Split),
+            ChartAction::Split => None,
+            _ if allowed(UserAction::Surrender) => Some(UserAction::Surrender),
+            ChartAction::SurrenderOrHit => Some(UserAction::Hit),
+            ChartAction::SurrenderOrStand => Some(UserAction::Stay),
+            ChartAction::SurrenderOrSplit => ChartAction::Split.resolve(actions),
+        }
+    }
+}
+
+impl FromStr for ChartAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let action = match s.to_ascii_uppercase().as_str() {
+            "H" => ChartAction::Hit,
+            "S" => ChartAction::Stand,
+            "D" => ChartAction::Double,
+            "DS" => ChartAction::DoubleOrStand,
+            "P" => ChartAction::Split,
+            "RH" => ChartAction::SurrenderOrHit,
+            "RS" => ChartAction::SurrenderOrStand,
+            "RP" => ChartAction::SurrenderOrSplit,
+            _ => return Err(s.to_string()),
+        };
+        Ok(action)
+    }
+}
+
+/// Dealer up-cards in chart column order.
+pub const DEALER_UP_CARDS: [&str; 10] = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "A"];
+
+pub const HARD_TOTALS: std::ops::RangeInclusive<i32> = 5..=21;
+pub const SOFT_TOTALS: std::ops::RangeInclusive<i32> = 13..=21;
+/// Pair card values, ace counted as 11.
+pub const PAIRS: std::ops::RangeInclusive<i32> = 2..=11;
+
+/// A full playing chart: rows by hard total, soft total and pair, columns by dealer up-card.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub hard: [[ChartAction; 10]; 17],
+    pub soft: [[ChartAction; 10]; 9],
+    pub pairs: [[ChartAction; 10]; 10],
+}
+
+impl Chart {
+    /// A chart to fill in, hit everything.
+    pub fn new() -> Self {
+        Chart {
+            hard: [[ChartAction::Hit; 10]; 17],
+            soft: [[ChartAction::Hit; 10]; 9],
+            pairs: [[ChartAction::Hit; 10]; 10],
+        }
+    }
+
+    /// Loads a chart in the format of `to_csv`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BlackjackError> {
+        Ok(fs::read_to_string(path)?.parse()?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BlackjackError> {
+        Ok(fs::write(path, self.to_csv())?)
+    }
+
+    /// The hard, soft and pairs tables, each under a heading row of dealer up-cards, e.g.
+    /// `hard,2,3,4,5,6,7,8,9,10,A` followed by `16,S,S,S,S,S,H,H,Rh,Rh,Rh`. Soft rows are
+    /// labeled `A2` to `AT`, pairs `22` to `TT` and `AA`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for section in [Section::Hard, Section::Soft, Section::Pairs].iter() {
+            if !csv.is_empty() {
+                csv.push('\n');
+            }
+            csv.push_str(section.to_str());
+            for up in DEALER_UP_CARDS.iter() {
+                csv.push(',');
+                csv.push_str(up);
+            }
+            csv.push('\n');
+            for (idx, cells) in self.section(*section).iter().enumerate() {
+                csv.push_str(&section.label(idx));
+                for cell in cells.iter() {
+                    csv.push(',');
+                    csv.push_str(cell.to_str());
+                }
+                csv.push('\n');
+            }
+        }
+        csv
+    }
+
+    fn section(&self, section: Section) -> &[[ChartAction; 10]] {
+        match section {
+            Section::Hard => &self.hard,
+            Section::Soft => &self.soft,
+            Section::Pairs => &self.pairs,
+        }
+    }
+
+    fn section_mut(&mut self, section: Section) -> &mut [[ChartAction; 10]] {
+        match section {
+            Section::Hard => &mut self.hard,
+            Section::Soft => &mut self.soft,
+            Section::Pairs => &mut self.pairs,
+        }
+    }
+
+    /// Column of the dealer up-card, 2 to ace.
+    pub fn column(dealer_up_card: &Card) -> usize {
+        (blackjack_card_value(&dealer_up_card.value) - 2).clamp(0, 9) as usize
+    }
+
+    pub fn hard_mut(&mut self, total: i32) -> &mut [ChartAction; 10] {
+        &mut self.hard[(total.clamp(5, 21) - 5) as usize]
+    }
+
+    pub fn soft_mut(&mut self, total: i32) -> &mut [ChartAction; 10] {
+        &mut self.soft[(total.clamp(13, 21) - 13) as usize]
+    }
+
+    pub fn pair_mut(&mut self, value: i32) -> &mut [ChartAction; 10] {
+        &mut self.pairs[(value.clamp(2, 11) - 2) as usize]
+    }
+
+    /// Chart cell for the hand, a pair is looked up by its total.
+    pub fn total_action(&self, hand: &Hand, dealer_up_card: &Card) -> ChartAction {
+        let column = Chart::column(dealer_up_card);
+        let value = hand.value;
+        if value.soft && value.total < 13 {
+            // two aces that can't be split
+            ChartAction::Hit
+        } else if value.soft {
+            self.soft[(value.total.min(21) - 13) as usize][column]
+        } else {
+            self.hard[(value.total.clamp(5, 21) - 5) as usize][column]
+        }
+    }
+
+    /// Chart cell for a two card pair, ten-valued cards count as a pair.
+    pub fn pair_action(&self, hand: &Hand, dealer_up_card: &Card) -> Option<ChartAction> {
+        let value = pair_value(hand)?;
+        Some(self.pairs[(value - 2) as usize][Chart::column(dealer_up_card)])
+    }
+
+    /// The chart's play for the hand, picked from the `actions` the table allows.
+    pub fn action(&self, hand: &Hand, dealer_up_card: &Card, actions: &[UserAction]) -> UserAction {
+        let action = self
+            .pair_action(hand, dealer_up_card)
+            .and_then(|cell| cell.resolve(actions))
+            .or_else(|| self.total_action(hand, dealer_up_card).resolve(actions))
+            .unwrap_or(UserAction::Stay);
+        if actions.contains(&action) {
+            action
+        } else {
+            UserAction::Stay
+        }
+    }
+
+    /// The chart says to surrender the hand as dealt.
+    pub fn surrenders(&self, hand: &Hand, dealer_up_card: &Card) -> bool {
+        self.pair_action(hand, dealer_up_card)
+            .unwrap_or_else(|| self.total_action(hand, dealer_up_card))
+            .is_surrender()
+    }
+}
+
+impl Default for Chart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the format of `to_csv`. Blank lines and lines starting with `#` are skipped, codes
+/// and labels are not case sensitive and `10` can stand in for `T`.
+impl FromStr for Chart {
+    type Err = ParseChartError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chart = Chart::new();
+        let mut rows = [vec![false; 17], vec![false; 9], vec![false; 10]];
+        let mut section = None;
+        for (idx, line) in s.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let cells: Vec<&str> = line
+                .split(',')
+                .map(|cell| cell.trim().trim_matches('"'))
+                .collect();
+            if cells.len() != DEALER_UP_CARDS.len() + 1 {
+                return Err(ParseChartError::Row { line: line_number });
+            }
+            let label_error = |label: &str| ParseChartError::Label {
+                line: line_number,
+                label: label.to_string(),
+            };
+
+            if let Some(heading) = Section::from_heading(cells[0]) {
+                for (up, cell) in (2..=11).zip(cells[1..].iter()) {
+                    if card_value(cell) != Some(up) {
+                        return Err(label_error(cell));
+                    }
+                }
+                section = Some(heading);
+                continue;
+            }
+
+            let section = section.ok_or(ParseChartError::Row { line: line_number })?;
+            let row = section.row(cells[0]).ok_or_else(|| label_error(cells[0]))?;
+            for (cell, code) in chart.section_mut(section)[row]
+                .iter_mut()
+                .zip(cells[1..].iter())
+            {
+                *cell = code.parse().map_err(|code| ParseChartError::Action {
+                    line: line_number,
+                    code,
+                })?;
+            }
+            rows[section as usize][row] = true;
+        }
+
+        for section in [Section::Hard, Section::Soft, Section::Pairs].iter() {
+            if let Some(row) = rows[*section as usize].iter().position(|seen| !seen) {
+                let row = format!("{} {}", section.to_str(), section.label(row));
+                return Err(ParseChartError::Missing(row));
+            }
+        }
+        Ok(chart)
+    }
+}
+
+/// The three tables one after the other, a row per hand and a column per dealer up-card.
+impl fmt::Display for Chart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = |f: &mut fmt::Formatter<'_>, title: &str| {
+            write!(f, "{:<6}", title)?;
+            for up in DEALER_UP_CARDS.iter() {
+                write!(f, "{:>4}", up)?;
+            }
+            writeln!(f)
+        };
+        let row = |f: &mut fmt::Formatter<'_>, label: String, cells: &[ChartAction; 10]| {
+            write!(f, "{:<6}", label)?;
+            for cell in cells.iter() {
+                write!(f, "{:>4}", cell.to_str())?;
+            }
+            writeln!(f)
+        };
+
+        header(f, "Hard")?;
+        for (total, cells) in HARD_TOTALS.zip(self.hard.iter()) {
+            row(f, total.to_string(), cells)?;
+        }
+        header(f, "Soft")?;
+        for (total, cells) in SOFT_TOTALS.zip(self.soft.iter()) {
+            row(f, format!("A,{}", total - 11), cells)?;
+        }
+        header(f, "Pairs")?;
+        for (value, cells) in PAIRS.zip(self.pairs.iter()) {
+            let card = if value == 11 {
+                "A".to_string()
+            } else {
+                value.to_string()
+            };
+            row(f, format!("{},{}", card, card), cells)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays a chart as is, e.g. one loaded from a file, never takes insurance.
+pub struct ChartStrategy {
+    pub chart: Chart,
+}
+
+impl ChartStrategy {
+    pub fn new(chart: Chart) -> Self {
+        ChartStrategy { chart }
+    }
+}
+
+impl Strategy for ChartStrategy {
+    fn action(
+        &mut self,
+        hand: &Hand,
+        dealer_up_card: &Card,
+        _rules: &TableRules,
+        actions: &[UserAction],
+    ) -> UserAction {
+        self.chart.action(hand, dealer_up_card, actions)
+    }
+
+    fn early_surrender(&mut self, hand: &Hand, dealer_up_card: &Card, _rules: &TableRules) -> bool {
+        self.chart.surrenders(hand, dealer_up_card)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Section {
+    Hard,
+    Soft,
+    Pairs,
+}
+
+impl Section {
+    fn to_str(self) -> &'static str {
+        match self {
+            Section::Hard => "hard",
+            Section::Soft => "soft",
+            Section::Pairs => "pairs",
+        }
+    }
+
+    fn from_heading(s: &str) -> Option<Section> {
+        match s.to_ascii_lowercase().as_str() {
+            "hard" => Some(Section::Hard),
+            "soft" => Some(Section::Soft),
+            "pairs" => Some(Section::Pairs),
+            _ => None,
+        }
+    }
+
+    /// Row label in the file, `16`, `A7` or `88`.
+    fn label(self, row: usize) -> String {
+        let card = |value: i32| match value {
+            10 => "T".to_string(),
+            11 => "A".to_string(),
+            _ => value.to_string(),
+        };
+        match self {
+            Section::Hard => (*HARD_TOTALS.start() + row as i32).to_string(),
+            Section::Soft => format!("A{}", card(*SOFT_TOTALS.start() - 11 + row as i32)),
+            Section::Pairs => {
+                let value = card(*PAIRS.start() + row as i32);
+                format!("{}{}", value, value)
+            }
+        }
+    }
+
+    /// Row of the label in this section's table.
+    fn row(self, label: &str) -> Option<usize> {
+        let label = label.to_ascii_uppercase();
+        let (value, range) = match self {
+            Section::Hard => (label.parse().ok(), HARD_TOTALS),
+            Section::Soft => (
+                label
+                    .strip_prefix('A')
+                    .and_then(card_value)
+                    .map(|value| value + 11),
+                SOFT_TOTALS,
+            ),
+            Section::Pairs => {
+                let middle = label.len() / 2;
+                let value = if label.is_char_boundary(middle) && label[..middle] == label[middle..]
+                {
+                    card_value(&label[..middle])
+                } else {
+                    None
+                };
+                (value, PAIRS)
+            }
+        };
+        value
+            .filter(|value| range.contains(value))
+            .map(|value| (value - range.start()) as usize)
+    }
+}
+
+/// Blackjack value of a card label, ace as 11.
+fn card_value(label: &str) -> Option<i32> {
+    match label.to_ascii_uppercase().as_str() {
+        "A" => Some(11),
+        "T" | "J" | "Q" | "K" => Some(10),
+        label => label.parse().ok().filter(|value| (2..=10).contains(value)),
+    }
+}
+
+/// Card value of a two card pair, ace as 11.
+fn pair_value(hand: &Hand) -> Option<i32> {
+    if hand.cards.len() != 2 {
+        return None;
+    }
+    let first = blackjack_card_value(&hand.cards[0].value);
+    let second = blackjack_card_value(&hand.cards[1].value);
+    if first == second && PAIRS.contains(&first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::rules::Surrender;
+    use crate::strategy::basic::basic_strategy_chart;
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let rules = TableRules {
+            surrender: Surrender::Late,
+            ..TableRules::default()
+        };
+        let chart = basic_strategy_chart(&rules);
+        let csv = chart.to_csv();
+        assert!(csv.contains("\n16,S,S,S,S,S,H,H,Rh,Rh,Rh\n"));
+        assert_eq!(csv.parse::<Chart>(), Ok(chart.clone()));
+
+        // spreadsheet exports: comments, quotes, windows line ends, 10 for T, any case
+        let edited = format!("# house chart\r\n{}", csv)
+            .replace(
+                "pairs,2,3,4,5,6,7,8,9,10,A",
+                "\"Pairs\",2,3,4,5,6,7,8,9,T,a",
+            )
+            .replace("TT,", "1010,")
+            .replace("A7,S,", "a7,s,");
+        assert_eq!(edited.parse::<Chart>(), Ok(chart));
+    }
+
+    #[test]
+    fn reports_where_a_chart_is_broken() {
+        let csv = basic_strategy_chart(&TableRules::default()).to_csv();
+        let errors = [
+            (
+                csv.replace("A7,S,", "A7,X,"),
+                ParseChartError::Action {
+                    line: 26,
+                    code: "X".to_string(),
+                },
+            ),
+            (
+                csv.replace("\n88,", "\n89,"),
+                ParseChartError::Label {
+                    line: 38,
+                    label: "89".to_string(),
+                },
+            ),
+            (
+                csv.replace("\n12,H,H,", "\n12,H,"),
+                ParseChartError::Row { line: 9 },
+            ),
+            (
+                csv.replace("hard,", "# hard,"),
+                ParseChartError::Row { line: 2 },
+            ),
+            (
+                csv.replace("\nA9,S,S,S,S,S,S,S,S,S,S", ""),
+                ParseChartError::Missing("soft A9".to_string()),
+            ),
+        ];
+        for (csv, error) in errors.iter() {
+            assert_eq!(csv.parse::<Chart>().as_ref(), Err(error));
+        }
+    }
+}
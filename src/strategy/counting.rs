@@ -0,0 +1,443 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::cards::card::Card;
+use crate::gameplay::blackjack::{blackjack_card_value, UserAction};
+use crate::gameplay::event::{GameEvent, GameObserver};
+use crate::gameplay::hand::Hand;
+use crate::gameplay::player::Player;
+use crate::gameplay::rules::TableRules;
+use crate::strategy::basic::basic_strategy_chart;
+use crate::strategy::chart::{Chart, ChartAction};
+use crate::strategy::Strategy;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CountingSystem {
+    pub name: &'static str,
+    /// Tag per card value, 2 to ace.
+    pub tags: [i32; 10],
+    /// Balanced systems count to zero over a full shoe and are converted to a true count,
+    /// unbalanced ones start at `4 - 4 * decks` and pivot at +4.
+    pub balanced: bool,
+    /// Hi-Lo points per point of this system, the index plays are Hi-Lo indices.
+    pub level: i32,
+}
+
+impl CountingSystem {
+    pub const HI_LO: CountingSystem = CountingSystem {
+        name: "Hi-Lo",
+        tags: [1, 1, 1, 1, 1, 0, 0, 0, -1, -1],
+        balanced: true,
+        level: 1,
+    };
+    pub const KO: CountingSystem = CountingSystem {
+        name: "KO",
+        tags: [1, 1, 1, 1, 1, 1, 0, 0, -1, -1],
+        balanced: false,
+        level: 1,
+    };
+    pub const HI_OPT_II: CountingSystem = CountingSystem {
+        name: "Hi-Opt II",
+        tags: [1, 1, 2, 2, 1, 1, 0, 0, -2, 0],
+        balanced: true,
+        level: 2,
+    };
+    pub const OMEGA_II: CountingSystem = CountingSystem {
+        name: "Omega II",
+        tags: [1, 1, 2, 2, 2, 1, 0, -1, -2, 0],
+        balanced: true,
+        level: 2,
+    };
+    pub const ZEN: CountingSystem = CountingSystem {
+        name: "Zen",
+        tags: [1, 1, 2, 2, 2, 1, 0, 0, -2, -1],
+        balanced: true,
+        level: 2,
+    };
+
+    pub fn tag(&self, card: &Card) -> i32 {
+        let value = blackjack_card_value(&card.value);
+        if (2..=11).contains(&value) {
+            self.tags[(value - 2) as usize]
+        } else {
+            0
+        }
+    }
+
+    fn initial_count(&self, number_of_decks: usize) -> i32 {
+        if self.balanced {
+            0
+        } else {
+            4 - 4 * number_of_decks as i32
+        }
+    }
+}
+
+/// Keeps the count from the cards leaving the shoe, the hole card is counted once revealed.
+#[derive(Debug, Clone)]
+pub struct CardCounter {
+    pub system: CountingSystem,
+    pub number_of_decks: usize,
+    pub running_count: i32,
+    /// Cards dealt since the last shuffle, face down ones included.
+    pub cards_dealt: usize,
+    /// Count and cards of the round being played, all that is left out of shuffled discards.
+    round_count: i32,
+    round_cards: usize,
+}
+
+impl CardCounter {
+    pub fn new(system: CountingSystem, number_of_decks: usize) -> Self {
+        CardCounter {
+            system,
+            number_of_decks,
+            running_count: system.initial_count(number_of_decks),
+            cards_dealt: 0,
+            round_count: 0,
+            round_cards: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.running_count = self.system.initial_count(self.number_of_decks);
+        self.cards_dealt = 0;
+    }
+
+    /// Decks left in the shoe, never less than half a deck.
+    pub fn remaining_decks(&self) -> f32 {
+        let remaining = (self.number_of_decks * 52).saturating_sub(self.cards_dealt);
+        (remaining as f32 / 52.0).max(0.5)
+    }
+
+    /// Count per remaining deck in Hi-Lo points, unbalanced systems are scaled around their
+    /// pivot.
+    pub fn true_count(&self) -> f32 {
+        let running_count = self.running_count as f32 / self.system.level as f32;
+        if self.system.balanced {
+            running_count / self.remaining_decks()
+        } else {
+            4.0 + (running_count - 4.0) / self.remaining_decks()
+        }
+    }
+}
+
+impl GameObserver for CardCounter {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::ShoeShuffled { number_of_decks } => {
+                self.number_of_decks = *number_of_decks;
+                self.reset();
+            }
+            GameEvent::DiscardsShuffled { .. } => {
+                // the discards are back in, only the cards on the table are out of the shoe
+                self.running_count =
+                    self.system.initial_count(self.number_of_decks) + self.round_count;
+                self.cards_dealt = self.round_cards;
+            }
+            GameEvent::RoundStarted { .. } => {
+                self.round_count = 0;
+                self.round_cards = 0;
+            }
+            GameEvent::CardDealt { card, .. } => {
+                self.cards_dealt += 1;
+                self.running_count += self.system.tag(card);
+                self.round_cards += 1;
+                self.round_count += self.system.tag(card);
+            }
+            GameEvent::HoleCardDealt { .. } => {
+                self.cards_dealt += 1;
+                self.round_cards += 1;
+            }
+            GameEvent::HoleCardRevealed { card } => {
+                self.running_count += self.system.tag(card);
+                self.round_count += self.system.tag(card);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Bet in table minimums by true count, the highest step not above the count wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetSpread {
+    /// `(true count, units)` in ascending true count order.
+    pub steps: Vec<(i32, i32)>,
+}
+
+impl BetSpread {
+    pub fn units(&self, true_count: f32) -> i32 {
+        self.steps
+            .iter()
+            .rev()
+            .find(|(count, _)| true_count >= *count as f32)
+            .map_or(1, |(_, units)| *units)
+    }
+}
+
+/// 1 to 8 units from +2 upwards.
+impl Default for BetSpread {
+    fn default() -> Self {
+        BetSpread {
+            steps: vec![(2, 2), (3, 4), (4, 6), (5, 8)],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IndexHand {
+    Hard(i32),
+    /// Pair by card value, ace as 11.
+    Pair(i32),
+}
+
+/// Plays `deviation` instead of the chart once the true count reaches `index`, or while it
+/// stays below when `below` is set.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct IndexPlay {
+    pub hand: IndexHand,
+    /// Dealer up-card, ace as 11.
+    pub up: i32,
+    pub index: i32,
+    pub below: bool,
+    pub deviation: ChartAction,
+}
+
+impl IndexPlay {
+    const fn at_or_above(hand: IndexHand, up: i32, index: i32, deviation: ChartAction) -> Self {
+        IndexPlay {
+            hand,
+            up,
+            index,
+            below: false,
+            deviation,
+        }
+    }
+
+    const fn below(hand: IndexHand, up: i32, index: i32, deviation: ChartAction) -> Self {
+        IndexPlay {
+            hand,
+            up,
+            index,
+            below: true,
+            deviation,
+        }
+    }
+
+    fn applies(&self, hand: &Hand, up: i32, true_count: f32) -> bool {
+        let matches_hand = match self.hand {
+            IndexHand::Hard(total) => !hand.value.soft && hand.value.total == total,
+            IndexHand::Pair(value) => {
+                hand.cards.len() == 2
+                    && hand
+                        .cards
+                        .iter()
+                        .all(|card| blackjack_card_value(&card.value) == value)
+            }
+        };
+        let reached = true_count >= self.index as f32;
+        matches_hand && self.up == up && reached != self.below
+    }
+}
+
+/// The Illustrious 18 Hi-Lo index plays, insurance at +3 is handled by `CountingStrategy`.
+pub const ILLUSTRIOUS_18: [IndexPlay; 17] = [
+    IndexPlay::at_or_above(IndexHand::Hard(16), 10, 0, ChartAction::Stand),
+    IndexPlay::at_or_above(IndexHand::Hard(15), 10, 4, ChartAction::Stand),
+    IndexPlay::at_or_above(IndexHand::Pair(10), 5, 5, ChartAction::Split),
+    IndexPlay::at_or_above(IndexHand::Pair(10), 6, 4, ChartAction::Split),
+    IndexPlay::at_or_above(IndexHand::Hard(10), 10, 4, ChartAction::Double),
+    IndexPlay::at_or_above(IndexHand::Hard(12), 3, 2, ChartAction::Stand),
+    IndexPlay::at_or_above(IndexHand::Hard(12), 2, 3, ChartAction::Stand),
+    IndexPlay::at_or_above(IndexHand::Hard(11), 11, 1, ChartAction::Double),
+    IndexPlay::at_or_above(IndexHand::Hard(9), 2, 1, ChartAction::Double),
+    IndexPlay::at_or_above(IndexHand::Hard(10), 11, 4, ChartAction::Double),
+    IndexPlay::at_or_above(IndexHand::Hard(9), 7, 3, ChartAction::Double),
+    IndexPlay::at_or_above(IndexHand::Hard(16), 9, 5, ChartAction::Stand),
+    IndexPlay::below(IndexHand::Hard(13), 2, -1, ChartAction::Hit),
+    IndexPlay::below(IndexHand::Hard(12), 4, 0, ChartAction::Hit),
+    IndexPlay::below(IndexHand::Hard(12), 5, -2, ChartAction::Hit),
+    IndexPlay::below(IndexHand::Hard(12), 6, -1, ChartAction::Hit),
+    IndexPlay::below(IndexHand::Hard(13), 3, -2, ChartAction::Hit),
+];
+
+/// The Fab 4 late surrender index plays.
+pub const FAB_4: [IndexPlay; 4] = [
+    IndexPlay::at_or_above(IndexHand::Hard(14), 10, 3, ChartAction::SurrenderOrHit),
+    IndexPlay::at_or_above(IndexHand::Hard(15), 10, 0, ChartAction::SurrenderOrHit),
+    IndexPlay::at_or_above(IndexHand::Hard(15), 9, 2, ChartAction::SurrenderOrHit),
+    IndexPlay::at_or_above(IndexHand::Hard(15), 11, 1, ChartAction::SurrenderOrHit),
+];
+
+/// Basic strategy with index plays, betting along the spread by true count.
+pub struct CountingStrategy {
+    pub counter: Rc<RefCell<CardCounter>>,
+    pub chart: Chart,
+    pub spread: BetSpread,
+    pub index_plays: Vec<IndexPlay>,
+    /// True count from which insurance and even money are taken.
+    pub insurance_index: i32,
+}
+
+impl CountingStrategy {
+    /// Register `counter` as an observer so it sees the cards.
+    pub fn new(system: CountingSystem, rules: &TableRules) -> Self {
+        let mut index_plays = FAB_4.to_vec();
+        index_plays.extend_from_slice(&ILLUSTRIOUS_18);
+        CountingStrategy {
+            counter: Rc::new(RefCell::new(CardCounter::new(
+                system,
+                rules.number_of_decks,
+            ))),
+            chart: basic_strategy_chart(rules),
+            spread: BetSpread::default(),
+            index_plays,
+            insurance_index: 3,
+        }
+    }
+
+    pub fn true_count(&self) -> f32 {
+        self.counter.borrow().true_count()
+    }
+}
+
+impl Strategy for CountingStrategy {
+    fn action(
+        &mut self,
+        hand: &Hand,
+        dealer_up_card: &Card,
+        _rules: &TableRules,
+        actions: &[UserAction],
+    ) -> UserAction {
+        let true_count = self.true_count();
+        let up = blackjack_card_value(&dealer_up_card.value);
+        let can_surrender = actions.contains(&UserAction::Surrender);
+        let chart_action = self.chart.action(hand, dealer_up_card, actions);
+        if matches!(chart_action, UserAction::Split | UserAction::Surrender) {
+            return chart_action;
+        }
+
+        for index_play in self.index_plays.iter() {
+            if index_play.deviation.is_surrender() && !can_surrender {
+                continue;
+            }
+            if !index_play.applies(hand, up, true_count) {
+                continue;
+            }
+            let action = match index_play.deviation {
+                ChartAction::Hit => UserAction::Hit,
+                ChartAction::Stand => UserAction::Stay,
+                ChartAction::Double | ChartAction::DoubleOrStand => UserAction::Double,
+                ChartAction::Split => UserAction::Split,
+                _ => UserAction::Surrender,
+            };
+            if actions.contains(&action) {
+                return action;
+            }
+        }
+        chart_action
+    }
+
+    fn insurance(&mut self, _hand: &Hand, _rules: &TableRules, maximum: i32) -> i32 {
+        if self.true_count() >= self.insurance_index as f32 {
+            maximum
+        } else {
+            0
+        }
+    }
+
+    fn even_money(&mut self, _hand: &Hand, _rules: &TableRules) -> bool {
+        self.true_count() >= self.insurance_index as f32
+    }
+
+    fn early_surrender(&mut self, hand: &Hand, dealer_up_card: &Card, _rules: &TableRules) -> bool {
+        self.chart.surrenders(hand, dealer_up_card)
+    }
+
+    fn bet(&mut self, _player: &Player, minimum: i32, maximum: i32) -> Option<i32> {
+        let units = self.spread.units(self.true_count());
+        Some((units * minimum).min(maximum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deal(counter: &mut CardCounter, card: &str) {
+        counter.on_event(&GameEvent::CardDealt {
+            actor_idx: 0,
+            hand_idx: 0,
+            card: card.parse().unwrap(),
+        });
+    }
+
+    #[test]
+    fn counts_cards_leaving_the_shoe() {
+        let mut counter = CardCounter::new(CountingSystem::HI_LO, 1);
+        for card in ["2S", "5H", "6D", "3C", "4S", "7H", "9D", "8C", "KS", "AH"].iter() {
+            deal(&mut counter, card);
+        }
+        counter.on_event(&GameEvent::HoleCardDealt { actor_idx: 1 });
+        assert_eq!(counter.running_count, 3);
+        counter.on_event(&GameEvent::HoleCardRevealed {
+            card: "2H".parse().unwrap(),
+        });
+        assert_eq!(counter.running_count, 4);
+        assert_eq!(counter.cards_dealt, 11);
+        assert!((counter.true_count() - 4.0 / (41.0 / 52.0)).abs() < 1e-4);
+
+        counter.on_event(&GameEvent::ShoeShuffled { number_of_decks: 2 });
+        assert_eq!(counter.running_count, 0);
+        assert_eq!(counter.cards_dealt, 0);
+        assert_eq!(CardCounter::new(CountingSystem::KO, 6).running_count, -20);
+    }
+
+    #[test]
+    fn keeps_the_cards_on_the_table_out_of_shuffled_discards() {
+        let mut counter = CardCounter::new(CountingSystem::HI_LO, 1);
+        for card in ["2S", "5H", "6D", "3C"].iter() {
+            deal(&mut counter, card);
+        }
+        counter.on_event(&GameEvent::RoundStarted {
+            actors: vec!["P1".to_string(), "Dealer".to_string()],
+            bets: vec![10],
+        });
+        for card in ["KS", "AH", "4D"].iter() {
+            deal(&mut counter, card);
+        }
+        counter.on_event(&GameEvent::HoleCardDealt { actor_idx: 1 });
+        assert_eq!(counter.running_count, 3);
+
+        counter.on_event(&GameEvent::DiscardsShuffled { cards: 4 });
+        assert_eq!(counter.running_count, -1);
+        assert_eq!(counter.cards_dealt, 4);
+        deal(&mut counter, "2C");
+        assert_eq!(counter.running_count, 0);
+        assert_eq!(counter.cards_dealt, 5);
+    }
+
+    #[test]
+    fn bets_and_deviates_by_true_count() {
+        let rules = TableRules::default();
+        let mut strategy = CountingStrategy::new(CountingSystem::HI_LO, &rules);
+        let player = Player::new("Bot".to_string(), 1000, 10);
+        let hand: Hand = "10S 6H".parse().unwrap();
+        let ten: Card = "KD".parse().unwrap();
+        let actions = [UserAction::Stay, UserAction::Hit, UserAction::Double];
+
+        strategy.counter.borrow_mut().running_count = -6;
+        assert_eq!(strategy.bet(&player, 10, 500), Some(10));
+        assert_eq!(
+            strategy.action(&hand, &ten, &rules, &actions),
+            UserAction::Hit
+        );
+
+        strategy.counter.borrow_mut().running_count = 30;
+        assert_eq!(strategy.bet(&player, 10, 500), Some(80));
+        assert_eq!(
+            strategy.action(&hand, &ten, &rules, &actions),
+            UserAction::Stay
+        );
+        assert_eq!(strategy.insurance(&hand, &rules, 5), 5);
+    }
+}
@@ -0,0 +1,199 @@
+//! Computer players: a `Strategy` decides for one seat, `TableDecisions` seats them next to
+//! human players.
+
+use crate::cards::card::Card;
+use crate::error::BlackjackError;
+use crate::gameplay::actor::Actor;
+use crate::gameplay::blackjack::UserAction;
+use crate::gameplay::decision::DecisionProvider;
+use crate::gameplay::hand::Hand;
+use crate::gameplay::player::Player;
+use crate::gameplay::rules::TableRules;
+
+pub mod analyzer;
+pub mod basic;
+pub mod chart;
+pub mod counting;
+
+pub trait Strategy {
+    /// One of `actions` for the hand.
+    fn action(
+        &mut self,
+        hand: &Hand,
+        dealer_up_card: &Card,
+        rules: &TableRules,
+        actions: &[UserAction],
+    ) -> UserAction;
+
+    /// Dealer shows an ace, insurance amount up to `maximum`.
+    fn insurance(&mut self, _hand: &Hand, _rules: &TableRules, _maximum: i32) -> i32 {
+        0
+    }
+
+    fn even_money(&mut self, _hand: &Hand, _rules: &TableRules) -> bool {
+        false
+    }
+
+    fn early_surrender(
+        &mut self,
+        _hand: &Hand,
+        _dealer_up_card: &Card,
+        _rules: &TableRules,
+    ) -> bool {
+        false
+    }
+
+    /// Flat betting at the table minimum.
+    fn bet(&mut self, _player: &Player, minimum: i32, _maximum: i32) -> Option<i32> {
+        Some(minimum)
+    }
+
+    /// Broke bots leave the table.
+    fn rebuy(&mut self, _player: &Player, _buy_in: i32) -> bool {
+        false
+    }
+}
+
+/// Routes every decision to the seat's strategy, seats without one are asked through `humans`.
+pub struct TableDecisions {
+    pub rules: TableRules,
+    /// Strategy per seat, `None` for a human player.
+    pub strategies: Vec<Option<Box<dyn Strategy>>>,
+    pub humans: Box<dyn DecisionProvider>,
+}
+
+impl TableDecisions {
+    /// A table of computer players only, nobody is asked for another round.
+    pub fn bots(rules: TableRules, strategies: Vec<Box<dyn Strategy>>) -> Self {
+        TableDecisions {
+            rules,
+            strategies: strategies.into_iter().map(Some).collect(),
+            humans: Box::new(NoHumans),
+        }
+    }
+
+    fn strategy(&mut self, seat: usize) -> Option<&mut Box<dyn Strategy>> {
+        self.strategies.get_mut(seat).and_then(Option::as_mut)
+    }
+}
+
+impl DecisionProvider for TableDecisions {
+    fn bet(
+        &mut self,
+        seat: usize,
+        player: &Player,
+        minimum: i32,
+        maximum: i32,
+    ) -> Result<Option<i32>, BlackjackError> {
+        match self.strategy(seat) {
+            Some(strategy) => Ok(strategy.bet(player, minimum, maximum)),
+            None => self.humans.bet(seat, player, minimum, maximum),
+        }
+    }
+
+    fn rebuy(&mut self, seat: usize, player: &Player, buy_in: i32) -> Result<bool, BlackjackError> {
+        match self.strategy(seat) {
+            Some(strategy) => Ok(strategy.rebuy(player, buy_in)),
+            None => self.humans.rebuy(seat, player, buy_in),
+        }
+    }
+
+    fn insurance(
+        &mut self,
+        seat: usize,
+        actor: &Actor,
+        maximum: i32,
+    ) -> Result<i32, BlackjackError> {
+        let rules = self.rules;
+        match self.strategy(seat) {
+            Some(strategy) => Ok(strategy.insurance(actor.hand_at(0)?, &rules, maximum)),
+            None => self.humans.insurance(seat, actor, maximum),
+        }
+    }
+
+    fn even_money(&mut self, seat: usize, actor: &Actor) -> Result<bool, BlackjackError> {
+        let rules = self.rules;
+        match self.strategy(seat) {
+            Some(strategy) => Ok(strategy.even_money(actor.hand_at(0)?, &rules)),
+            None => self.humans.even_money(seat, actor),
+        }
+    }
+
+    fn early_surrender(
+        &mut self,
+        seat: usize,
+        actor: &Actor,
+        dealer_up_card: &Card,
+    ) -> Result<bool, BlackjackError> {
+        let rules = self.rules;
+        match self.strategy(seat) {
+            Some(strategy) => {
+                Ok(strategy.early_surrender(actor.hand_at(0)?, dealer_up_card, &rules))
+            }
+            None => self.humans.early_surrender(seat, actor, dealer_up_card),
+        }
+    }
+
+    fn action(
+        &mut self,
+        seat: usize,
+        actor: &Actor,
+        hand_idx: usize,
+        dealer_up_card: &Card,
+        actions: &[UserAction],
+    ) -> Result<UserAction, BlackjackError> {
+        let rules = self.rules;
+        match self.strategy(seat) {
+            Some(strategy) => {
+                Ok(strategy.action(actor.hand_at(hand_idx)?, dealer_up_card, &rules, actions))
+            }
+            None => self
+                .humans
+                .action(seat, actor, hand_idx, dealer_up_card, actions),
+        }
+    }
+
+    fn another_round(&mut self) -> Result<bool, BlackjackError> {
+        self.humans.another_round()
+    }
+}
+
+/// Stands in for the humans at a table of bots.
+struct NoHumans;
+
+impl DecisionProvider for NoHumans {
+    fn bet(&mut self, _: usize, _: &Player, _: i32, _: i32) -> Result<Option<i32>, BlackjackError> {
+        Err(BlackjackError::InputClosed)
+    }
+
+    fn rebuy(&mut self, _: usize, _: &Player, _: i32) -> Result<bool, BlackjackError> {
+        Err(BlackjackError::InputClosed)
+    }
+
+    fn insurance(&mut self, _: usize, _: &Actor, _: i32) -> Result<i32, BlackjackError> {
+        Err(BlackjackError::InputClosed)
+    }
+
+    fn even_money(&mut self, _: usize, _: &Actor) -> Result<bool, BlackjackError> {
+        Err(BlackjackError::InputClosed)
+    }
+
+    fn early_surrender(&mut self, _: usize, _: &Actor, _: &Card) -> Result<bool, BlackjackError> {
+        Err(BlackjackError::InputClosed)
+    }
+
+    fn action(
+        &mut self,
+        _: usize,
+        _: &Actor,
+        _: usize,
+        _: &Card,
+        _: &[UserAction],
+    ) -> Result<UserAction, BlackjackError> {
+        Err(BlackjackError::InputClosed)
+    }
+
+    fn another_round(&mut self) -> Result<bool, BlackjackError> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,402 @@
+use rust_blackjack::cards::card::{Card, CardSymbol, Suit};
+use rust_blackjack::error::BlackjackError;
+use rust_blackjack::gameplay::actor::Actor;
+use rust_blackjack::gameplay::blackjack::UserAction;
+use rust_blackjack::gameplay::decision::DecisionProvider;
+use rust_blackjack::gameplay::event::{GameEvent, GameObserver};
+use rust_blackjack::gameplay::game::HandResult;
+use rust_blackjack::gameplay::hand::Hand;
+use rust_blackjack::gameplay::player::Player;
+use rust_blackjack::gameplay::wager::Wager;
+
+/// Prompts the players sitting at the terminal.
+pub struct TerminalInput;
+
+impl DecisionProvider for TerminalInput {
+    fn bet(
+        &mut self,
+        _seat: usize,
+        player: &Player,
+        minimum: i32,
+        maximum: i32,
+    ) -> Result<Option<i32>, BlackjackError> {
+        let prompt = format!(
+            "{}: bet? [{}-{}, enter to repeat {}, c to cash out]:",
+            player.name, minimum, maximum, player.last_bet
+        );
+
+        loop {
+            let input = take_stdin_string!(prompt, 1)?;
+            if input == "c" {
+                return Ok(None);
+            }
+            let bet = if input.is_empty() {
+                Ok(player.last_bet)
+            } else {
+                input.parse::<i32>()
+            };
+            match bet {
+                Ok(bet) if (minimum..=maximum).contains(&bet) => return Ok(Some(bet)),
+                _ => println!("Invalid bet."),
+            }
+        }
+    }
+
+    fn rebuy(
+        &mut self,
+        _seat: usize,
+        player: &Player,
+        buy_in: i32,
+    ) -> Result<bool, BlackjackError> {
+        let prompt = format!(
+            "{}: {} coins can't cover the table minimum, rebuy {}? [y/n]:",
+            player.name, player.bankroll, buy_in
+        );
+        Ok(take_stdin_key!(prompt, 'y', 'n')? == 'y')
+    }
+
+    fn insurance(
+        &mut self,
+        _seat: usize,
+        actor: &Actor,
+        maximum: i32,
+    ) -> Result<i32, BlackjackError> {
+        let prompt = format!(
+            "{}: dealer shows an ace, insurance? [0-{}]:",
+            actor.name, maximum
+        );
+        loop {
+            match take_stdin_string!(prompt, 1)?.parse::<i32>() {
+                Ok(amount) if (0..=maximum).contains(&amount) => return Ok(amount),
+                _ => println!("Invalid amount."),
+            }
+        }
+    }
+
+    fn even_money(&mut self, _seat: usize, actor: &Actor) -> Result<bool, BlackjackError> {
+        let prompt = format!(
+            "{}: dealer shows an ace, take even money? [y/n]:",
+            actor.name
+        );
+        Ok(take_stdin_key!(prompt, 'y', 'n')? == 'y')
+    }
+
+    fn early_surrender(
+        &mut self,
+        _seat: usize,
+        actor: &Actor,
+        _dealer_up_card: &Card,
+    ) -> Result<bool, BlackjackError> {
+        let prompt = format!("{}: early surrender? [y/n]:", actor.name);
+        Ok(take_stdin_key!(prompt, 'y', 'n')? == 'y')
+    }
+
+    fn action(
+        &mut self,
+        _seat: usize,
+        actor: &Actor,
+        hand_idx: usize,
+        _dealer_up_card: &Card,
+        actions: &[UserAction],
+    ) -> Result<UserAction, BlackjackError> {
+        let names: Vec<&str> = actions.iter().map(|action| action.to_str()).collect();
+        let keys: Vec<String> = actions
+            .iter()
+            .map(|action| action.key().to_string())
+            .collect();
+        let prompt = format!(
+            "{}:{} {}? [{}]:",
+            actor.name,
+            hand_idx + 1,
+            names.join("/"),
+            keys.join("/")
+        );
+
+        loop {
+            let key = take_stdin_key!(prompt, 's', 'h', 'd', 'x', 'r')?;
+            match actions.iter().find(|action| action.key() == key) {
+                Some(&action) => {
+                    return Ok(action);
+                }
+                None => {
+                    println!("Invalid command.");
+                    continue;
+                }
+            }
+        }
+    }
+
+    fn another_round(&mut self) -> Result<bool, BlackjackError> {
+        println!();
+        Ok(take_stdin_key!("Another round? [y/n]:", 'y', 'n')? == 'y')
+    }
+}
+
+/// Prints the table from the game events, pausing now and then so a human can follow along.
+#[derive(Default)]
+pub struct TerminalRenderer {
+    actors: Vec<String>,
+    /// The table as seen from the events, dealer last.
+    hands: Vec<Vec<Hand>>,
+    /// Initial cards are shown all at once.
+    dealing: bool,
+    /// Cards still to come before the table is printed again, both halves of a split.
+    pending_cards: usize,
+    settling: bool,
+}
+
+impl TerminalRenderer {
+    fn is_dealer(&self, actor_idx: usize) -> bool {
+        actor_idx + 1 == self.actors.len()
+    }
+
+    fn hand_mut(&mut self, actor_idx: usize, hand_idx: usize) -> Option<&mut Hand> {
+        self.hands.get_mut(actor_idx)?.get_mut(hand_idx)
+    }
+
+    fn name(&self, actor_idx: usize) -> &str {
+        self.actors.get(actor_idx).map_or("?", String::as_str)
+    }
+
+    /// `Hand`, or `Dealer` for the dealer's hand.
+    fn who(&self, actor_idx: usize) -> &str {
+        if self.is_dealer(actor_idx) {
+            "Dealer"
+        } else {
+            "Hand"
+        }
+    }
+
+    fn print_table(&self) {
+        for (name, hands) in self.actors.iter().zip(self.hands.iter()) {
+            for (idx, hand) in hands.iter().enumerate() {
+                match hand.describe() {
+                    Ok(cards) => println!("{}:{} {{ {} }}", name, idx + 1, cards),
+                    Err(err) => println!("{}:{} {}", name, idx + 1, err),
+                }
+            }
+        }
+    }
+
+    fn pause_before_settling(&mut self) {
+        if !self.settling {
+            self.settling = true;
+            simulate_think!(1);
+        }
+    }
+}
+
+impl GameObserver for TerminalRenderer {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::ShoeShuffled { number_of_decks } => {
+                println!("Cut card is out --> SHUFFLING {} DECKS \n", number_of_decks);
+            }
+            GameEvent::DiscardsShuffled { cards } => {
+                println!("Shoe is empty --> SHUFFLING {} DISCARDS \n", cards);
+            }
+            GameEvent::RoundStarted { actors, bets } => {
+                self.actors = actors.clone();
+                self.hands = actors
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, _)| {
+                        let mut hand = Hand::new();
+                        hand.wager = Wager::new(bets.get(idx).copied().unwrap_or(0));
+                        vec![hand]
+                    })
+                    .collect();
+                self.dealing = true;
+                self.pending_cards = 0;
+                self.settling = false;
+            }
+            GameEvent::CardDealt {
+                actor_idx,
+                hand_idx,
+                card,
+            } => {
+                if let Some(hand) = self.hand_mut(*actor_idx, *hand_idx) {
+                    hand.deal_card(card.clone());
+                    let _ = hand.update_state();
+                }
+                if self.dealing {
+                    return;
+                }
+                if self.pending_cards > 0 {
+                    self.pending_cards -= 1;
+                    if self.pending_cards > 0 {
+                        return;
+                    }
+                }
+                if self.is_dealer(*actor_idx) {
+                    simulate_think!(2);
+                }
+                self.print_table();
+            }
+            GameEvent::HoleCardDealt { actor_idx } => {
+                // Stands in for the hole card until it is revealed, it is never shown.
+                let mut hole_card = Card::new(Suit::Spade, CardSymbol::Two);
+                hole_card.hide();
+                if let Some(hand) = self.hand_mut(*actor_idx, 0) {
+                    hand.deal_card(hole_card);
+                    let _ = hand.update_state();
+                }
+            }
+            GameEvent::InitialCardsDealt => {
+                self.dealing = false;
+                self.print_table();
+            }
+            GameEvent::InsuranceTaken { actor_idx, amount } => {
+                println!("{} --> INSURANCE {} \n", self.name(*actor_idx), amount);
+            }
+            GameEvent::EvenMoneyTaken { actor_idx } => {
+                println!("{} --> EVEN MONEY \n", self.name(*actor_idx));
+            }
+            GameEvent::DealerPeeked { blackjack } => {
+                simulate_think!(1);
+                if *blackjack {
+                    println!("Dealer peeks --> BLACKJACK! \n");
+                } else {
+                    println!("Dealer peeks --> no blackjack \n");
+                }
+            }
+            GameEvent::HoleCardRevealed { card } => {
+                let dealer_idx = self.actors.len().saturating_sub(1);
+                if let Some(hand) = self.hand_mut(dealer_idx, 0) {
+                    if let Ok(hole_card) = hand.card_at_mut(1) {
+                        *hole_card = card.clone();
+                    }
+                    let _ = hand.update_state();
+                }
+                simulate_think!(2);
+                self.print_table();
+                simulate_think!(2);
+            }
+            GameEvent::ActionTaken {
+                actor_idx,
+                hand_idx,
+                action,
+            } => {
+                let mut soft_17 = false;
+                if let Some(hand) = self.hand_mut(*actor_idx, *hand_idx) {
+                    if *action == UserAction::Double {
+                        hand.wager.double = hand.wager.main;
+                    }
+                    soft_17 = hand.value.soft && hand.value.total == 17;
+                }
+                if self.is_dealer(*actor_idx) && *action == UserAction::Hit && soft_17 {
+                    println!("Dealer --> HIT (soft 17) \n");
+                } else if *action == UserAction::Surrender {
+                    println!("{} --> SURRENDER \n", self.name(*actor_idx));
+                } else {
+                    println!("{} --> {} \n", self.who(*actor_idx), action.to_str());
+                }
+            }
+            GameEvent::HandSplit {
+                actor_idx,
+                hand_idx,
+            } => {
+                if let Some(hands) = self.hands.get_mut(*actor_idx) {
+                    if let Some(hand) = hands.get_mut(*hand_idx) {
+                        hand.split = true;
+                        let mut new_hand =
+                            Hand::from_cards(hand.cards.pop_back().into_iter().collect());
+                        new_hand.split = true;
+                        new_hand.wager = Wager::new(hand.wager.main);
+                        hands.insert(hand_idx + 1, new_hand);
+                    }
+                }
+                self.pending_cards = 2;
+            }
+            GameEvent::HandBusted { actor_idx, .. } => {
+                println!("{} --> BUST \n", self.who(*actor_idx));
+            }
+            GameEvent::HandNatural { actor_idx, .. } => {
+                if self.is_dealer(*actor_idx) {
+                    println!("Dealer --> BLACKJACK! \n");
+                } else {
+                    println!("Hand --> BlackJack! \n");
+                }
+            }
+            GameEvent::HandTwentyOne { actor_idx, .. } => {
+                println!("{} --> 21 \n", self.who(*actor_idx));
+            }
+            GameEvent::InsuranceSettled { player, coins } => {
+                self.pause_before_settling();
+                print_insurance_result(player, *coins);
+            }
+            GameEvent::HandSettled {
+                player,
+                result,
+                coins,
+                ..
+            } => {
+                self.pause_before_settling();
+                print_hand_result(result, player, *coins);
+            }
+            GameEvent::PlayerRebought { player, amount } => {
+                println!("{} --> REBUY {} \n", player, amount);
+            }
+            GameEvent::PlayerSatOut { player } => {
+                println!("{} --> SITS OUT \n", player);
+            }
+            GameEvent::PlayerCashedOut { player, bankroll } => {
+                println!("{} --> CASH OUT {} coins \n", player, bankroll);
+            }
+        }
+    }
+}
+
+fn print_hand_result(result: &HandResult, player_name: &str, coins: i32) {
+    match result {
+        HandResult::AutoWin => println!("{} : +{:2} coins --> AUTO BLACKJACK!", player_name, coins),
+        HandResult::Win => println!("{} : +{:2} coins --> WON", player_name, coins),
+        HandResult::Draw => println!("{} : +{:2} coins --> DRAW", player_name, coins),
+        HandResult::EvenMoney => {
+            println!("{} : +{:2} coins --> EVEN MONEY", player_name, coins)
+        }
+        HandResult::Surrender => {
+            println!("{} : -{:2} coins --> SURRENDER", player_name, -coins)
+        }
+        HandResult::Loss => println!("{} : -{:2} coins --> LOSS", player_name, -coins),
+    }
+}
+
+fn print_insurance_result(player_name: &str, coins: i32) {
+    if coins > 0 {
+        println!("{} : +{:2} coins --> INSURANCE PAID", player_name, coins);
+    } else {
+        println!("{} : -{:2} coins --> INSURANCE LOST", player_name, -coins);
+    }
+}
+
+pub fn print_player_scores(players: &[Player]) {
+    println!();
+    println!("Scores:");
+    println!("=======");
+    for player in players.iter() {
+        if player.seated {
+            println!("{}: {} coins", player.name, player.bankroll);
+        } else {
+            println!("{}: {} coins (cashed out)", player.name, player.bankroll);
+        }
+    }
+}
+
+pub fn print_session_summary(players: &[Player]) {
+    println!();
+    println!("Session summary:");
+    println!("================");
+    for player in players.iter() {
+        let net = player.net_result();
+        println!(
+            "{}: bought in {}, peak {}, final {}, net {}{}",
+            player.name,
+            player.buy_ins,
+            player.peak,
+            player.bankroll,
+            if net > 0 { "+" } else { "" },
+            net
+        );
+    }
+}